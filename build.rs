@@ -0,0 +1,25 @@
+use std::env;
+
+fn main() {
+    let embed_feature = env::var_os("CARGO_FEATURE_EMBED").is_some();
+    let hot_reload_feature = env::var_os("CARGO_FEATURE_HOT_RELOAD").is_some();
+    let debug_assertions = env::var_os("CARGO_CFG_DEBUG_ASSERTIONS").is_some();
+
+    // without any of the features, the profile decides
+    let (embed, hot_reload) = if embed_feature || hot_reload_feature {
+        (embed_feature, hot_reload_feature)
+    } else {
+        (!debug_assertions, debug_assertions)
+    };
+
+    println!("cargo:rustc-check-cfg=cfg(embed)");
+    println!("cargo:rustc-check-cfg=cfg(hot_reload)");
+
+    if embed {
+        println!("cargo:rustc-cfg=embed");
+    }
+
+    if hot_reload {
+        println!("cargo:rustc-cfg=hot_reload");
+    }
+}
@@ -5,7 +5,7 @@ extern crate rocket;
 extern crate rocket_include_static_resources;
 
 use rocket::State;
-use rocket_include_static_resources::{EtagIfNoneMatch, StaticContextManager, StaticResponse};
+use rocket_include_static_resources::{Preconditions, StaticContextManager, StaticResponse};
 
 static_response_handler! {
     "/favicon.ico" => favicon => "favicon",
@@ -22,9 +22,9 @@ cached_static_response_handler! {
 #[get("/")]
 fn index(
     static_resources: &State<StaticContextManager>,
-    etag_if_none_match: EtagIfNoneMatch,
+    preconditions: Preconditions,
 ) -> StaticResponse {
-    static_resources.build(&etag_if_none_match, "html-readme")
+    static_resources.build(preconditions, "html-readme")
 }
 
 #[launch]
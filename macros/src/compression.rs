@@ -0,0 +1,30 @@
+use std::io::Write;
+
+use flate2::{write::GzEncoder, Compression};
+
+/// Compress the data with every supported encoding at the highest level. The results are ordered by preference, and the ones which are not smaller than the original data are dropped.
+pub(crate) fn compress(data: &[u8]) -> Vec<(&'static str, Vec<u8>)> {
+    let mut encoded = Vec::with_capacity(3);
+
+    {
+        let mut writer = brotli::CompressorWriter::new(Vec::new(), 4096, 11, 22);
+
+        writer.write_all(data).unwrap();
+
+        encoded.push(("Brotli", writer.into_inner()));
+    }
+
+    encoded.push(("Zstd", zstd::bulk::compress(data, 19).unwrap()));
+
+    {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::best());
+
+        encoder.write_all(data).unwrap();
+
+        encoded.push(("Gzip", encoder.finish().unwrap()));
+    }
+
+    encoded.retain(|(_, compressed)| compressed.len() < data.len());
+
+    encoded
+}
@@ -0,0 +1,208 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use globset::{GlobBuilder, GlobMatcher};
+use syn::{
+    parse::{Parse, ParseStream},
+    Expr, Ident, LitStr, Token,
+};
+
+use crate::join_builder::JoinBuilder;
+
+/// `$crate, $resources, $directory[, include = "glob"][, exclude = "glob"]`
+pub(crate) struct DirectoryInput {
+    pub(crate) krate:     proc_macro2::TokenStream,
+    pub(crate) resources: Expr,
+    pub(crate) directory: PathBuf,
+    pub(crate) include:   Option<LitStr>,
+    pub(crate) exclude:   Option<LitStr>,
+}
+
+impl Parse for DirectoryInput {
+    fn parse(input: ParseStream) -> Result<Self, syn::Error> {
+        let krate = crate::parse_crate_path(input)?;
+
+        let resources = input.parse::<Expr>()?;
+
+        input.parse::<Token![,]>()?;
+
+        let directory = JoinBuilder::parse_one(input)?;
+
+        let mut include = None;
+        let mut exclude = None;
+
+        while input.peek(Token![,]) {
+            input.parse::<Token![,]>()?;
+
+            if input.is_empty() {
+                break;
+            }
+
+            let key = input.parse::<Ident>()?;
+
+            input.parse::<Token![=]>()?;
+
+            let value = input.parse::<LitStr>()?;
+
+            let slot = match key.to_string().as_str() {
+                "include" => &mut include,
+                "exclude" => &mut exclude,
+                _ => return Err(syn::Error::new_spanned(key, "expected `include` or `exclude`")),
+            };
+
+            if slot.is_some() {
+                return Err(syn::Error::new_spanned(key, "duplicate option"));
+            }
+
+            *slot = Some(value);
+        }
+
+        Ok(DirectoryInput {
+            krate,
+            resources,
+            directory,
+            include,
+            exclude,
+        })
+    }
+}
+
+/// `*` and `?` do not match `/`, so `**/` is needed to match files in subdirectories.
+fn compile_glob(glob: &LitStr) -> Result<GlobMatcher, syn::Error> {
+    GlobBuilder::new(&glob.value())
+        .literal_separator(true)
+        .build()
+        .map(|glob| glob.compile_matcher())
+        .map_err(|err| syn::Error::new_spanned(glob, err))
+}
+
+/// `ancestors` are the canonical paths of the directories being walked, so that a symbolic link to one of them, which would be a loop, is not followed.
+fn walk(
+    directory: &Path,
+    relative: &str,
+    ancestors: &mut Vec<PathBuf>,
+    files: &mut Vec<(String, PathBuf)>,
+) -> io::Result<()> {
+    let canonical = fs::canonicalize(directory)?;
+
+    if ancestors.contains(&canonical) {
+        return Ok(());
+    }
+
+    ancestors.push(canonical);
+
+    let mut entries = fs::read_dir(directory)?.collect::<Result<Vec<_>, _>>()?;
+
+    entries.sort_by_key(|entry| entry.file_name());
+
+    for entry in entries {
+        let path = entry.path();
+
+        let file_name = match entry.file_name().into_string() {
+            Ok(file_name) => file_name,
+            Err(file_name) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("{:?} is not a UTF-8 file name", file_name),
+                ))
+            },
+        };
+
+        let name =
+            if relative.is_empty() { file_name } else { format!("{}/{}", relative, file_name) };
+
+        // follow symbolic links
+        if fs::metadata(&path)?.is_dir() {
+            walk(&path, &name, ancestors, files)?;
+        } else {
+            files.push((name, path));
+        }
+    }
+
+    ancestors.pop();
+
+    Ok(())
+}
+
+/// List the files in the directory which match the globs, with their paths relative to the directory, separated by `/`. Symbolic links are followed, except the ones to a directory which contains them.
+pub(crate) fn list_files(input: &DirectoryInput) -> Result<Vec<(String, PathBuf)>, syn::Error> {
+    let include = input.include.as_ref().map(compile_glob).transpose()?;
+    let exclude = input.exclude.as_ref().map(compile_glob).transpose()?;
+
+    let mut files = Vec::new();
+
+    walk(&input.directory, "", &mut Vec::new(), &mut files).map_err(|err| {
+        syn::Error::new(
+            proc_macro2::Span::call_site(),
+            format!("Cannot read the directory {:?}: {}", input.directory, err),
+        )
+    })?;
+
+    files.retain(|(name, _)| {
+        include.as_ref().map(|glob| glob.is_match(name)).unwrap_or(true)
+            && !exclude.as_ref().map(|glob| glob.is_match(name)).unwrap_or(false)
+    });
+
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::process;
+
+    use syn::parse_quote;
+
+    use super::*;
+
+    fn list(directory: &Path, include: Option<&str>, exclude: Option<&str>) -> Vec<String> {
+        let glob = |glob: &str| LitStr::new(glob, proc_macro2::Span::call_site());
+
+        let input = DirectoryInput {
+            krate:     quote::quote!(krate),
+            resources: parse_quote!(resources),
+            directory: directory.to_path_buf(),
+            include:   include.map(glob),
+            exclude:   exclude.map(glob),
+        };
+
+        list_files(&input).unwrap().into_iter().map(|(name, _)| name).collect()
+    }
+
+    #[test]
+    fn list_matching_files() {
+        let directory = std::env::temp_dir().join(format!("directory-test-{}", process::id()));
+
+        fs::create_dir_all(directory.join("css/vendor")).unwrap();
+
+        for name in
+            ["index.html", "app.css", "css/site.css", "css/site.css.map", "css/vendor/a.css"]
+        {
+            fs::write(directory.join(name), name).unwrap();
+        }
+
+        // a loop, which is not followed
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(&directory, directory.join("css/root")).unwrap();
+
+        // sorted by name, separated by `/`
+        assert_eq!(
+            ["app.css", "css/site.css", "css/site.css.map", "css/vendor/a.css", "index.html"],
+            list(&directory, None, None).as_slice()
+        );
+
+        // `*` does not match `/`
+        assert_eq!(["app.css"], list(&directory, Some("*.css"), None).as_slice());
+        assert_eq!(
+            ["app.css", "css/site.css", "css/vendor/a.css"],
+            list(&directory, Some("**/*.css"), None).as_slice()
+        );
+        assert_eq!(
+            ["app.css", "css/site.css", "index.html"],
+            list(&directory, None, Some("{**/*.map,**/vendor/**}")).as_slice()
+        );
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
+}
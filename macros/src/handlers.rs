@@ -0,0 +1,152 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{
+    parse::{Parse, ParseStream},
+    Expr, ExprLit, Ident, Lit, Token,
+};
+
+use crate::names::unwrap_groups;
+
+/// `$route => $handler_name => $name`
+pub(crate) struct Handler {
+    route:        Expr,
+    handler_name: Ident,
+    name:         Expr,
+}
+
+impl Parse for Handler {
+    fn parse(input: ParseStream) -> Result<Self, syn::Error> {
+        let route = input.parse::<Expr>()?;
+
+        input.parse::<Token![=>]>()?;
+
+        let handler_name = input.parse::<Ident>()?;
+
+        input.parse::<Token![=>]>()?;
+
+        let name = input.parse::<Expr>()?;
+
+        Ok(Handler {
+            route,
+            handler_name,
+            name,
+        })
+    }
+}
+
+/// `$crate, [cache($max_age, $must_revalidate);] $($route => $handler_name => $name),*`
+pub(crate) struct HandlersInput {
+    krate:    TokenStream,
+    cache:    Option<(Expr, Expr)>,
+    handlers: Vec<Handler>,
+}
+
+impl Parse for HandlersInput {
+    fn parse(input: ParseStream) -> Result<Self, syn::Error> {
+        let krate = crate::parse_crate_path(input)?;
+
+        let cache = if input.peek(Ident) && input.peek2(syn::token::Paren) {
+            let key = input.parse::<Ident>()?;
+
+            if key != "cache" {
+                return Err(syn::Error::new_spanned(key, "expected `cache`"));
+            }
+
+            let content;
+            syn::parenthesized!(content in input);
+
+            let max_age = content.parse::<Expr>()?;
+
+            content.parse::<Token![,]>()?;
+
+            let must_revalidate = content.parse::<Expr>()?;
+
+            input.parse::<Token![;]>()?;
+
+            Some((max_age, must_revalidate))
+        } else {
+            None
+        };
+
+        let handlers =
+            input.parse_terminated(Handler::parse, Token![,])?.into_iter().collect::<Vec<_>>();
+
+        Ok(HandlersInput {
+            krate,
+            cache,
+            handlers,
+        })
+    }
+}
+
+/// Check whether a name is known at compile time, i.e. it is a literal string, or a path to a constant or a typed handle.
+fn is_constant(name: &Expr) -> bool {
+    matches!(
+        unwrap_groups(name),
+        Expr::Lit(ExprLit {
+            lit: Lit::Str(_),
+            ..
+        }) | Expr::Path(_)
+    )
+}
+
+pub(crate) fn expand(input: HandlersInput) -> TokenStream {
+    let krate = &input.krate;
+
+    let handlers = input.handlers.iter().map(|handler| {
+        let Handler {
+            route,
+            handler_name,
+            name,
+        } = handler;
+
+        // a name known at compile time is checked by a sentinel when Rocket launches
+        let (name_type, response_type) = if is_constant(name) {
+            let name_type_name = format_ident!("__static_resource_name_of_{}", handler_name);
+
+            (
+                Some(quote! {
+                    #[doc(hidden)]
+                    #[allow(non_camel_case_types)]
+                    struct #name_type_name;
+
+                    impl #krate::ResourceName for #name_type_name {
+                        #[inline]
+                        fn name() -> &'static str {
+                            #krate::ResourceKey::name(&#name)
+                        }
+                    }
+                }),
+                quote!(#krate::NamedStaticResponse<#name_type_name>),
+            )
+        } else {
+            (None, quote!(#krate::StaticResponse))
+        };
+
+        let (return_type, body) = match &input.cache {
+            Some((max_age, must_revalidate)) => (
+                quote!(#krate::CacheResponse<#response_type>),
+                quote! {
+                    let responder = static_resources.build(preconditions, #name).into();
+
+                    #krate::CacheResponse::public_only_release(responder, #max_age, #must_revalidate)
+                },
+            ),
+            None => (response_type, quote!(static_resources.build(preconditions, #name).into())),
+        };
+
+        quote! {
+            #name_type
+
+            #[#krate::rocket::get(#route)]
+            fn #handler_name(
+                static_resources: &#krate::rocket::State<#krate::StaticContextManager>,
+                preconditions: #krate::Preconditions,
+            ) -> #return_type {
+                #body
+            }
+        }
+    });
+
+    quote!(#(#handlers)*)
+}
@@ -0,0 +1,73 @@
+use std::{env, path::PathBuf};
+
+use syn::{
+    parse::{Parse, ParseStream},
+    Expr, Lit, Token,
+};
+
+/// A path made of literal strings or nested literal string tuples, relative to **CARGO_MANIFEST_DIR** unless it is absolute.
+pub(crate) struct JoinBuilder(pub(crate) PathBuf);
+
+fn handle_expr(expr: Expr, path: &mut PathBuf) -> Result<(), syn::Error> {
+    match expr {
+        Expr::Lit(lit) => match lit.lit {
+            Lit::Str(s) => path.push(s.value()),
+            lit => return Err(syn::Error::new_spanned(lit, "not a literal string")),
+        },
+        Expr::Tuple(tuple) => {
+            for expr in tuple.elems {
+                handle_expr(expr, path)?;
+            }
+        },
+        // `$x:expr` fragments passed from `macro_rules!` macros end up in invisible groups
+        Expr::Group(group) => return handle_expr(*group.expr, path),
+        Expr::Paren(paren) => return handle_expr(*paren.expr, path),
+        _ => {
+            return Err(syn::Error::new_spanned(
+                expr,
+                "not a literal string or a literal string tuple",
+            ));
+        },
+    }
+
+    Ok(())
+}
+
+fn resolve(path: PathBuf) -> PathBuf {
+    if path.is_relative() {
+        let manifest_dir = env::var_os("CARGO_MANIFEST_DIR").expect("we need CARGO_MANIFEST_DIR");
+
+        PathBuf::from(manifest_dir).join(path)
+    } else {
+        path
+    }
+}
+
+impl JoinBuilder {
+    /// Parse a single literal string or literal string tuple, which may be followed by other arguments.
+    pub(crate) fn parse_one(input: ParseStream) -> Result<PathBuf, syn::Error> {
+        let mut path = PathBuf::new();
+
+        handle_expr(input.parse::<Expr>()?, &mut path)?;
+
+        Ok(resolve(path))
+    }
+}
+
+impl Parse for JoinBuilder {
+    fn parse(input: ParseStream) -> Result<Self, syn::Error> {
+        let mut path = PathBuf::new();
+
+        while !input.is_empty() {
+            handle_expr(input.parse::<Expr>()?, &mut path)?;
+
+            if input.peek(Token![,]) {
+                input.parse::<Token![,]>()?;
+            } else {
+                break;
+            }
+        }
+
+        Ok(JoinBuilder(resolve(path)))
+    }
+}
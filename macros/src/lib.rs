@@ -0,0 +1,248 @@
+/*!
+# Include Static Resources for Rocket Framework (Macros)
+
+This crate provides procedural macros used by the `rocket-include-static-resources` crate. They are not meant to be used directly.
+*/
+
+#[cfg(feature = "compression")]
+mod compression;
+mod directory;
+mod handlers;
+mod join_builder;
+mod metadata;
+mod names;
+mod table;
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use join_builder::JoinBuilder;
+use proc_macro::TokenStream;
+use proc_macro2::TokenTree;
+use quote::quote;
+use sha2::{Digest, Sha256, Sha384};
+use syn::{
+    parse::{Parse, ParseStream},
+    parse_macro_input, Token,
+};
+
+/// The path of the `rocket-include-static-resources` crate (usually `$crate`) followed by a comma and a path.
+struct CratePathAndPath {
+    krate: proc_macro2::TokenStream,
+    path:  JoinBuilder,
+}
+
+/// Parse the tokens before the first comma, and the comma.
+fn parse_crate_path(input: ParseStream) -> Result<proc_macro2::TokenStream, syn::Error> {
+    let mut krate = proc_macro2::TokenStream::new();
+
+    while !input.peek(Token![,]) {
+        krate.extend(Some(input.parse::<TokenTree>()?));
+    }
+
+    input.parse::<Token![,]>()?;
+
+    Ok(krate)
+}
+
+impl Parse for CratePathAndPath {
+    fn parse(input: ParseStream) -> Result<Self, syn::Error> {
+        let krate = parse_crate_path(input)?;
+
+        let path = input.parse::<JoinBuilder>()?;
+
+        Ok(CratePathAndPath {
+            krate,
+            path,
+        })
+    }
+}
+
+/// Expand to a `static_resources_initialize!` call which registers every file in a directory under its path relative to the directory. The arguments are the path of the `rocket-include-static-resources` crate, the resources, the directory, and optional `include = "glob"` and `exclude = "glob"` options.
+#[proc_macro]
+pub fn initialize_directory(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as directory::DirectoryInput);
+
+    let files = match directory::list_files(&input) {
+        Ok(files) => files,
+        Err(err) => return err.into_compile_error().into(),
+    };
+
+    let mut entries = Vec::with_capacity(files.len());
+
+    for (name, path) in files {
+        let path = match path.to_str() {
+            Some(path) => path.to_string(),
+            None => {
+                let message =
+                    format!("The path {:?} cannot be represented as a UTF-8 string.", path);
+
+                return quote!(compile_error!(#message)).into();
+            },
+        };
+
+        entries.push(quote!(#name => #path));
+    }
+
+    let krate = input.krate;
+    let resources = input.resources;
+
+    quote! {
+        #krate::static_resources_initialize!(#resources, #(#entries),*)
+    }
+    .into()
+}
+
+/// Generate **GET** route handlers which respond static resources. The arguments are the path of the `rocket-include-static-resources` crate, an optional `cache(max_age, must_revalidate);`, and `route => handler_name => name` entries. The names which are literal strings are checked by a sentinel when Rocket launches.
+#[proc_macro]
+pub fn static_response_handlers(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as handlers::HandlersInput);
+
+    handlers::expand(input).into()
+}
+
+/// Generate a `ResourceMetadata` from the metadata of a resource in the initializer. The arguments are the path of the `rocket-include-static-resources` crate, the path of the file, and optionally the metadata in braces. A `charset` is appended to the MIME type, which is guessed from the extension of the file if `mime` is not given.
+#[proc_macro]
+pub fn resource_metadata(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as metadata::MetadataInput);
+
+    metadata::expand(input).unwrap_or_else(syn::Error::into_compile_error).into()
+}
+
+/// Generate a `&'static [StaticEntry]` table of embedded resources, sorted by name. The arguments are the path of the `rocket-include-static-resources` crate and `name => path` entries, whose names must be literal strings.
+#[proc_macro]
+pub fn static_table(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as table::TableInput);
+
+    table::expand(input).into()
+}
+
+/// Report duplicate resource names as compile errors. It expands to nothing if the names are unique.
+#[proc_macro]
+pub fn check_unique_names(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as names::Names);
+
+    names::check_unique(input).into()
+}
+
+/// Get the time this macro is expanded, which is roughly when the crate using it is built, as a tuple of the seconds since the Unix epoch and the HTTP-date. The `SOURCE_DATE_EPOCH` environment variable is respected for reproducible builds.
+#[proc_macro]
+pub fn build_time(_input: TokenStream) -> TokenStream {
+    let timestamp = std::env::var("SOURCE_DATE_EPOCH")
+        .ok()
+        .and_then(|timestamp| timestamp.trim().parse::<u64>().ok())
+        .unwrap_or_else(|| {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|duration| duration.as_secs())
+                .unwrap_or(0)
+        });
+
+    let http_date = httpdate::fmt_http_date(UNIX_EPOCH + Duration::from_secs(timestamp));
+
+    quote!((#timestamp, #http_date)).into()
+}
+
+/// Read a file and compute its entity tag, which is the same as the one `EntityTag::from_data` computes at runtime, as a literal string with the double quotes, so it is also the value of the `ETag` header.
+#[proc_macro]
+pub fn include_etag(input: TokenStream) -> TokenStream {
+    let path = parse_macro_input!(input as JoinBuilder).0;
+
+    match std::fs::read(&path) {
+        Ok(data) => {
+            let etag = entity_tag::EntityTag::from_data(&data).to_string();
+
+            quote!(#etag).into()
+        },
+        Err(err) => {
+            let message = format!("Cannot read {:?}: {}", path, err);
+
+            quote!(compile_error!(#message)).into()
+        },
+    }
+}
+
+/// Read a file and compute its Subresource Integrity metadata (`sha384-...`) and the value of its `Content-Digest` header (`sha-256=:...:`), as a tuple of two literal strings, which are the same as the ones computed at runtime.
+#[proc_macro]
+pub fn include_digests(input: TokenStream) -> TokenStream {
+    let path = parse_macro_input!(input as JoinBuilder).0;
+
+    match std::fs::read(&path) {
+        Ok(data) => {
+            let integrity = compute_integrity(&data);
+            let content_digest = compute_content_digest(&data);
+
+            quote!((#integrity, #content_digest)).into()
+        },
+        Err(err) => {
+            let message = format!("Cannot read {:?}: {}", path, err);
+
+            quote!(compile_error!(#message)).into()
+        },
+    }
+}
+
+#[inline]
+fn compute_integrity(data: &[u8]) -> String {
+    format!("sha384-{}", STANDARD.encode(Sha384::digest(data)))
+}
+
+#[inline]
+fn compute_content_digest(data: &[u8]) -> String {
+    format!("sha-256=:{}:", STANDARD.encode(Sha256::digest(data)))
+}
+
+/// Include the precompressed representations of a file, their quoted entity tags and the values of their `Content-Digest` headers as a `&'static [(ContentEncoding, &'static [u8], &'static str, &'static str)]`, ordered by preference. The first argument is the path of the `rocket-include-static-resources` crate. The file is not read, and the slice is empty, unless the `compression` feature is enabled.
+#[proc_macro]
+pub fn include_encoded(input: TokenStream) -> TokenStream {
+    let CratePathAndPath {
+        krate,
+        path,
+    } = parse_macro_input!(input as CratePathAndPath);
+
+    #[cfg(feature = "compression")]
+    {
+        let path = path.0;
+
+        let data = match std::fs::read(&path) {
+            Ok(data) => data,
+            Err(err) => {
+                let message = format!("Cannot read {:?}: {}", path, err);
+
+                return quote!(compile_error!(#message)).into();
+            },
+        };
+
+        let encoded = compression::compress(&data).into_iter().map(|(encoding, compressed)| {
+            let encoding = syn::Ident::new(encoding, proc_macro2::Span::call_site());
+            let etag = entity_tag::EntityTag::from_data(&compressed).to_string();
+            let content_digest = compute_content_digest(&compressed);
+            let compressed = proc_macro2::Literal::byte_string(&compressed);
+
+            quote!((#krate::ContentEncoding::#encoding, #compressed, #etag, #content_digest))
+        });
+
+        quote! {
+            {
+                let encoded: &'static [(#krate::ContentEncoding, &'static [u8], &'static str, &'static str)] = &[#(#encoded),*];
+
+                encoded
+            }
+        }
+        .into()
+    }
+
+    #[cfg(not(feature = "compression"))]
+    {
+        let _ = path.0;
+
+        quote! {
+            {
+                let encoded: &'static [(#krate::ContentEncoding, &'static [u8], &'static str, &'static str)] = &[];
+
+                encoded
+            }
+        }
+        .into()
+    }
+}
@@ -0,0 +1,308 @@
+use std::{collections::HashSet, path::PathBuf};
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{
+    braced,
+    parse::{Parse, ParseStream},
+    punctuated::Punctuated,
+    Ident, LitStr, Token,
+};
+
+use crate::{join_builder::JoinBuilder, parse_crate_path};
+
+/// The headers which are set by the responses, so they cannot be given as extra headers.
+const RESERVED_HEADERS: [&str; 9] = [
+    "content-length",
+    "content-encoding",
+    "content-digest",
+    "content-range",
+    "etag",
+    "last-modified",
+    "accept-ranges",
+    "vary",
+    "transfer-encoding",
+];
+
+struct Header {
+    name:  LitStr,
+    value: LitStr,
+}
+
+impl Parse for Header {
+    fn parse(input: ParseStream) -> Result<Self, syn::Error> {
+        let name = input.parse::<LitStr>()?;
+
+        input.parse::<Token![:]>()?;
+
+        let value = input.parse::<LitStr>()?;
+
+        let name_string = name.value();
+
+        if name_string.is_empty()
+            || !name_string
+                .bytes()
+                .all(|c| c.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&c))
+        {
+            return Err(syn::Error::new_spanned(&name, "not a valid header name"));
+        }
+
+        let lowercase_name = name_string.to_ascii_lowercase();
+
+        if lowercase_name == "content-type" {
+            return Err(syn::Error::new_spanned(
+                &name,
+                "use `mime` to set the `Content-Type` header",
+            ));
+        }
+
+        if RESERVED_HEADERS.contains(&lowercase_name.as_str()) {
+            return Err(syn::Error::new_spanned(
+                &name,
+                format!("The `{}` header is set by the response.", name_string),
+            ));
+        }
+
+        if value.value().bytes().any(|c| c == b'\r' || c == b'\n' || c == 0) {
+            return Err(syn::Error::new_spanned(&value, "not a valid header value"));
+        }
+
+        Ok(Header {
+            name,
+            value,
+        })
+    }
+}
+
+enum Value {
+    Mime(LitStr),
+    Charset(LitStr),
+    Headers(Vec<Header>),
+}
+
+struct Field {
+    key:   Ident,
+    value: Value,
+}
+
+impl Parse for Field {
+    fn parse(input: ParseStream) -> Result<Self, syn::Error> {
+        let key = input.parse::<Ident>()?;
+
+        input.parse::<Token![:]>()?;
+
+        let value = match key.to_string().as_str() {
+            "mime" => Value::Mime(input.parse()?),
+            "charset" => Value::Charset(input.parse()?),
+            "headers" => {
+                let content;
+
+                braced!(content in input);
+
+                let headers = Punctuated::<Header, Token![,]>::parse_terminated(&content)?;
+
+                Value::Headers(headers.into_iter().collect())
+            },
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    key,
+                    "unknown metadata, expected `mime`, `charset` or `headers`",
+                ));
+            },
+        };
+
+        Ok(Field {
+            key,
+            value,
+        })
+    }
+}
+
+/// The path of the `rocket-include-static-resources` crate, the path of a file, and optional metadata in braces, in which every key and every header name can be given once.
+pub(crate) struct MetadataInput {
+    krate:   TokenStream,
+    path:    PathBuf,
+    mime:    Option<LitStr>,
+    charset: Option<LitStr>,
+    headers: Vec<Header>,
+}
+
+impl Parse for MetadataInput {
+    fn parse(input: ParseStream) -> Result<Self, syn::Error> {
+        let krate = parse_crate_path(input)?;
+
+        let path = JoinBuilder::parse_one(input)?;
+
+        let mut mime = None;
+        let mut charset = None;
+        let mut headers = Vec::new();
+
+        if input.peek(Token![,]) {
+            input.parse::<Token![,]>()?;
+
+            let content;
+
+            braced!(content in input);
+
+            let mut keys = HashSet::new();
+            let mut header_names = HashSet::new();
+
+            for Field {
+                key,
+                value,
+            } in Punctuated::<Field, Token![,]>::parse_terminated(&content)?
+            {
+                if !keys.insert(key.to_string()) {
+                    return Err(syn::Error::new_spanned(
+                        &key,
+                        format!("`{}` is given more than once", key),
+                    ));
+                }
+
+                match value {
+                    Value::Mime(s) => mime = Some(s),
+                    Value::Charset(s) => charset = Some(s),
+                    Value::Headers(h) => {
+                        for header in h {
+                            if !header_names.insert(header.name.value().to_ascii_lowercase()) {
+                                return Err(syn::Error::new_spanned(
+                                    &header.name,
+                                    format!(
+                                        "The `{}` header is given more than once.",
+                                        header.name.value()
+                                    ),
+                                ));
+                            }
+
+                            headers.push(header);
+                        }
+                    },
+                }
+            }
+        }
+
+        Ok(MetadataInput {
+            krate,
+            path,
+            mime,
+            charset,
+            headers,
+        })
+    }
+}
+
+pub(crate) fn expand(input: MetadataInput) -> Result<TokenStream, syn::Error> {
+    let krate = &input.krate;
+
+    let mime = match (&input.mime, &input.charset) {
+        (None, None) => None,
+        (Some(mime), None) => {
+            check_mime(mime, &mime.value())?;
+
+            Some(mime.value())
+        },
+        (mime, Some(charset)) => {
+            let essence = match mime {
+                Some(mime) => mime.value(),
+                None => mime_guess::from_path(&input.path).first_or_octet_stream().to_string(),
+            };
+
+            let mime_string = format!("{}; charset={}", essence, charset.value());
+
+            let parsed = check_mime(charset, &mime_string)?;
+
+            if parsed.params().filter(|(name, _)| *name == mime::CHARSET).count() > 1 {
+                return Err(syn::Error::new_spanned(
+                    charset,
+                    "the MIME type already has a charset",
+                ));
+            }
+
+            Some(mime_string)
+        },
+    };
+
+    let mime = match mime {
+        Some(mime) => quote!(Some(#mime)),
+        None => quote!(None),
+    };
+
+    let headers = input.headers.iter().map(
+        |Header {
+             name,
+             value,
+         }| quote!((#name, #value)),
+    );
+
+    Ok(quote!(#krate::ResourceMetadata::new(#mime, &[#(#headers),*])))
+}
+
+fn check_mime(span: &LitStr, mime: &str) -> Result<mime::Mime, syn::Error> {
+    mime.parse::<mime::Mime>().map_err(|err| {
+        syn::Error::new_spanned(span, format!("`{}` is not a valid MIME type: {}", mime, err))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_error(metadata: &str) -> String {
+        match syn::parse_str::<MetadataInput>(&format!("krate, \"a.txt\", {{ {} }}", metadata)) {
+            Ok(_) => panic!("{:?} is accepted", metadata),
+            Err(err) => err.to_string(),
+        }
+    }
+
+    #[test]
+    fn parse_metadata() {
+        let input = syn::parse_str::<MetadataInput>(
+            r#"krate, "a.txt", { mime: "text/plain", charset: "utf-8", headers: { "X-A": "1", "X-B": "2" } }"#,
+        )
+        .unwrap();
+
+        assert_eq!(input.mime.unwrap().value(), "text/plain");
+        assert_eq!(input.charset.unwrap().value(), "utf-8");
+        assert_eq!(input.headers.len(), 2);
+    }
+
+    #[test]
+    fn reject_repeated_keys() {
+        assert_eq!(
+            parse_error(r#"mime: "text/plain", mime: "text/html""#),
+            "`mime` is given more than once"
+        );
+        assert_eq!(
+            parse_error(r#"charset: "utf-8", charset: "utf-8""#),
+            "`charset` is given more than once"
+        );
+        assert_eq!(
+            parse_error(r#"headers: { "X-A": "1" }, headers: { "X-B": "2" }"#),
+            "`headers` is given more than once"
+        );
+    }
+
+    #[test]
+    fn reject_reserved_headers() {
+        assert_eq!(
+            parse_error(r#"headers: { "Content-Digest": "sha-256=:AAAA:" }"#),
+            "The `Content-Digest` header is set by the response."
+        );
+        assert_eq!(
+            parse_error(r#"headers: { "etag": "\"a\"" }"#),
+            "The `etag` header is set by the response."
+        );
+        assert_eq!(
+            parse_error(r#"headers: { "Content-Type": "text/plain" }"#),
+            "use `mime` to set the `Content-Type` header"
+        );
+    }
+
+    #[test]
+    fn reject_repeated_headers() {
+        assert_eq!(
+            parse_error(r#"headers: { "X-A": "1", "x-a": "2" }"#),
+            "The `x-a` header is given more than once."
+        );
+    }
+}
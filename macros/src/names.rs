@@ -0,0 +1,93 @@
+use proc_macro2::{Span, TokenStream};
+use quote::{quote, ToTokens};
+use sha2::{Digest, Sha256};
+use syn::{
+    parse::{Parse, ParseStream},
+    punctuated::Punctuated,
+    Expr, ExprLit, Ident, Lit, Token,
+};
+
+/// Resource names separated by commas.
+pub(crate) struct Names(Punctuated<Expr, Token![,]>);
+
+impl Parse for Names {
+    #[inline]
+    fn parse(input: ParseStream) -> Result<Self, syn::Error> {
+        Ok(Names(Punctuated::parse_terminated(input)?))
+    }
+}
+
+/// Look into the invisible groups of `$x:expr` fragments.
+pub(crate) fn unwrap_groups(expr: &Expr) -> &Expr {
+    match expr {
+        Expr::Group(group) => unwrap_groups(&group.expr),
+        Expr::Paren(paren) => unwrap_groups(&paren.expr),
+        _ => expr,
+    }
+}
+
+/// Report the names which appear more than once. Literal strings are compared by their values, and other expressions, such as paths to constants, are compared by their tokens.
+///
+/// If the names are unique, an item is declared for each of them, so that a name which is also registered by another invocation in the same block, e.g. an explicit entry and a file found by `static_resources_initialize_directory!`, is a compile error as well (the item is defined multiple times).
+pub(crate) fn check_unique(names: Names) -> TokenStream {
+    let mut seen: Vec<String> = Vec::with_capacity(names.0.len());
+    let mut error: Option<syn::Error> = None;
+
+    for expr in names.0.iter() {
+        let expr = unwrap_groups(expr);
+
+        let key = match expr {
+            Expr::Lit(ExprLit {
+                lit: Lit::Str(s), ..
+            }) => format!("{:?}", s.value()),
+            expr => format!("`{}`", expr.to_token_stream().to_string().replace(' ', "")),
+        };
+
+        if seen.contains(&key) {
+            let new_error = syn::Error::new_spanned(
+                expr,
+                format!("The name {} is registered more than once.", key),
+            );
+
+            match error.as_mut() {
+                Some(error) => error.combine(new_error),
+                None => error = Some(new_error),
+            }
+        } else {
+            seen.push(key);
+        }
+    }
+
+    if let Some(error) = error {
+        return error.into_compile_error();
+    }
+
+    let markers = seen.iter().map(|key| marker(key));
+
+    quote! {
+        #(
+            #[allow(dead_code, non_camel_case_types)]
+            struct #markers;
+        )*
+    }
+}
+
+/// The identifier of the item declared for a name, which is readable in the error and contains the hash of the name, so that different names never share it.
+fn marker(key: &str) -> Ident {
+    let readable =
+        key.chars().map(|c| if c.is_ascii_alphanumeric() { c } else { '_' }).collect::<String>();
+
+    let hash = Sha256::digest(key.as_bytes());
+
+    Ident::new(
+        &format!(
+            "__static_resource_named_{}_{:02x}{:02x}{:02x}{:02x}",
+            readable.trim_matches('_'),
+            hash[0],
+            hash[1],
+            hash[2],
+            hash[3]
+        ),
+        Span::call_site(),
+    )
+}
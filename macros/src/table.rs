@@ -0,0 +1,122 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{
+    braced,
+    parse::{Parse, ParseStream},
+    punctuated::Punctuated,
+    Expr, ExprLit, Lit, LitStr, Token,
+};
+
+use crate::{names::unwrap_groups, parse_crate_path};
+
+struct TableEntry {
+    name:     LitStr,
+    path:     Expr,
+    metadata: Option<TokenStream>,
+}
+
+impl Parse for TableEntry {
+    fn parse(input: ParseStream) -> Result<Self, syn::Error> {
+        let name = input.parse::<Expr>()?;
+
+        let name = match unwrap_groups(&name) {
+            Expr::Lit(ExprLit {
+                lit: Lit::Str(s), ..
+            }) => s.clone(),
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    name,
+                    "a name in a table must be a literal string, so that the table can be sorted \
+                     at compile time",
+                ));
+            },
+        };
+
+        input.parse::<Token![=>]>()?;
+
+        let path = input.parse::<Expr>()?;
+
+        let metadata = if input.peek(Token![;]) {
+            input.parse::<Token![;]>()?;
+
+            let content;
+
+            braced!(content in input);
+
+            Some(content.parse::<TokenStream>()?)
+        } else {
+            None
+        };
+
+        Ok(TableEntry {
+            name,
+            path,
+            metadata,
+        })
+    }
+}
+
+/// The path of the `rocket-include-static-resources` crate followed by a comma and `name => path` entries, each optionally followed by `; { metadata }`.
+pub(crate) struct TableInput {
+    krate:   TokenStream,
+    entries: Vec<TableEntry>,
+}
+
+impl Parse for TableInput {
+    fn parse(input: ParseStream) -> Result<Self, syn::Error> {
+        let krate = parse_crate_path(input)?;
+
+        let entries =
+            Punctuated::<TableEntry, Token![,]>::parse_terminated(input)?.into_iter().collect();
+
+        Ok(TableInput {
+            krate,
+            entries,
+        })
+    }
+}
+
+pub(crate) fn expand(input: TableInput) -> TokenStream {
+    let krate = &input.krate;
+
+    let mut entries = input.entries;
+
+    // sorted by the bytes of the names, which is the order of `str::cmp`
+    entries.sort_by_key(|entry| entry.name.value());
+
+    for pair in entries.windows(2) {
+        if pair[0].name.value() == pair[1].name.value() {
+            return syn::Error::new_spanned(
+                &pair[1].name,
+                format!("The name {:?} is registered more than once.", pair[1].name.value()),
+            )
+            .into_compile_error();
+        }
+    }
+
+    let count = entries.len();
+
+    let entries = entries.iter().map(|TableEntry { name, path, metadata }| {
+        let metadata = metadata.as_ref().map(|metadata| quote!(, { #metadata }));
+
+        quote! {
+            #krate::StaticEntry::new(
+                #name,
+                #krate::manifest_dir_macros::mime_guess!(default = "application/octet-stream", #path),
+                include_bytes!(#krate::manifest_dir_macros::path!(#path)),
+                #krate::rocket_include_static_resources_macros::include_etag!(#path),
+                #krate::rocket_include_static_resources_macros::include_digests!(#path),
+                #krate::rocket_include_static_resources_macros::include_encoded!(#krate, #path),
+                #krate::rocket_include_static_resources_macros::resource_metadata!(#krate, #path #metadata),
+            )
+        }
+    });
+
+    quote! {
+        {
+            static TABLE: [#krate::StaticEntry; #count] = [#(#entries),*];
+
+            &TABLE
+        }
+    }
+}
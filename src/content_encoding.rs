@@ -0,0 +1,213 @@
+use std::fmt::{self, Display, Formatter};
+
+/// A content coding which a precompressed representation of a resource is encoded with.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum ContentEncoding {
+    /// `br`
+    Brotli,
+    /// `zstd`
+    Zstd,
+    /// `gzip`
+    Gzip,
+}
+
+impl ContentEncoding {
+    /// Get the token used in the `Content-Encoding` and `Accept-Encoding` headers.
+    #[inline]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            ContentEncoding::Brotli => "br",
+            ContentEncoding::Zstd => "zstd",
+            ContentEncoding::Gzip => "gzip",
+        }
+    }
+
+    /// Pick the most acceptable encoding from `available` according to the value of an `Accept-Encoding` header. `None` means the identity representation should be used.
+    ///
+    /// Encodings with the same quality value are preferred in the order they are given, and to the identity representation.
+    pub fn negotiate<I: IntoIterator<Item = ContentEncoding>>(
+        accept_encoding: Option<&str>,
+        available: I,
+    ) -> Option<ContentEncoding> {
+        let accept_encoding = accept_encoding?;
+
+        let mut wildcard = None;
+        let mut identity = None;
+        let mut codings: Vec<(&str, f32)> = Vec::new();
+
+        for item in accept_encoding.split(',') {
+            let mut parts = item.split(';');
+
+            let coding = parts.next().unwrap_or("").trim();
+
+            if coding.is_empty() {
+                continue;
+            }
+
+            let mut q = 1.0;
+
+            for param in parts {
+                let param = param.trim();
+
+                if let Some(value) = param.strip_prefix("q=").or_else(|| param.strip_prefix("Q=")) {
+                    q = match value.trim().parse::<f32>() {
+                        Ok(value) if (0.0..=1.0).contains(&value) => value,
+                        _ => 0.0,
+                    };
+                }
+            }
+
+            if coding == "*" {
+                wildcard = Some(q);
+            } else if coding.eq_ignore_ascii_case("identity") {
+                identity = Some(q);
+            } else {
+                codings.push((coding, q));
+            }
+        }
+
+        let quality = |encoding: ContentEncoding| {
+            codings
+                .iter()
+                .find(|(coding, _)| {
+                    coding.eq_ignore_ascii_case(encoding.as_str())
+                        || (encoding == ContentEncoding::Gzip
+                            && coding.eq_ignore_ascii_case("x-gzip"))
+                })
+                .map(|(_, q)| *q)
+                .or(wildcard)
+                .unwrap_or(0.0)
+        };
+
+        // the identity representation is the fallback when no acceptable encoding is available, and it only competes with the encodings when its quality value is given
+        let mut best = (None, identity.or(wildcard).unwrap_or(0.0));
+
+        for encoding in available {
+            let q = quality(encoding);
+
+            if q > 0.0 && (q > best.1 || (q == best.1 && best.0.is_none())) {
+                best = (Some(encoding), q);
+            }
+        }
+
+        best.0
+    }
+}
+
+impl Display for ContentEncoding {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        f.write_str(self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AVAILABLE: [ContentEncoding; 3] =
+        [ContentEncoding::Brotli, ContentEncoding::Zstd, ContentEncoding::Gzip];
+
+    fn negotiate(accept_encoding: Option<&str>) -> Option<ContentEncoding> {
+        ContentEncoding::negotiate(accept_encoding, AVAILABLE)
+    }
+
+    #[test]
+    fn negotiate_without_header() {
+        assert_eq!(None, negotiate(None));
+        assert_eq!(None, negotiate(Some("")));
+    }
+
+    #[test]
+    fn negotiate_quality_values() {
+        assert_eq!(Some(ContentEncoding::Gzip), negotiate(Some("gzip")));
+        assert_eq!(Some(ContentEncoding::Gzip), negotiate(Some("GZIP;Q=1")));
+        assert_eq!(Some(ContentEncoding::Gzip), negotiate(Some("x-gzip")));
+        assert_eq!(Some(ContentEncoding::Gzip), negotiate(Some("br;q=0.5, gzip;q=0.8")));
+        assert_eq!(Some(ContentEncoding::Zstd), negotiate(Some("gzip;q=0.5, zstd")));
+
+        assert_eq!(None, negotiate(Some("gzip;q=0")));
+        assert_eq!(None, negotiate(Some("gzip;q=2")));
+        assert_eq!(None, negotiate(Some("gzip;q=abc")));
+        assert_eq!(Some(ContentEncoding::Brotli), negotiate(Some("gzip;q=0, br;q=0.1")));
+    }
+
+    #[test]
+    fn negotiate_equal_quality_values() {
+        // the encodings are preferred in the order they are given
+        assert_eq!(Some(ContentEncoding::Brotli), negotiate(Some("gzip, zstd, br")));
+        assert_eq!(
+            Some(ContentEncoding::Gzip),
+            ContentEncoding::negotiate(Some("br, gzip"), [
+                ContentEncoding::Gzip,
+                ContentEncoding::Brotli
+            ])
+        );
+
+        // an encoding is preferred to the identity representation
+        assert_eq!(Some(ContentEncoding::Gzip), negotiate(Some("identity, gzip")));
+        assert_eq!(None, negotiate(Some("identity, gzip;q=0.5")));
+    }
+
+    #[test]
+    fn negotiate_wildcard() {
+        assert_eq!(Some(ContentEncoding::Brotli), negotiate(Some("*")));
+        assert_eq!(Some(ContentEncoding::Zstd), negotiate(Some("*;q=0.5, br;q=0.1")));
+        assert_eq!(Some(ContentEncoding::Gzip), negotiate(Some("*;q=0, gzip")));
+        assert_eq!(None, negotiate(Some("*;q=0")));
+    }
+
+    #[test]
+    fn negotiate_identity() {
+        assert_eq!(Some(ContentEncoding::Gzip), negotiate(Some("identity;q=0, gzip;q=0.1")));
+        // nothing acceptable is available, so the identity representation is still used
+        assert_eq!(None, negotiate(Some("identity;q=0")));
+        assert_eq!(None, ContentEncoding::negotiate(Some("gzip"), []));
+    }
+
+    #[test]
+    fn negotiate_unknown_codings() {
+        assert_eq!(None, negotiate(Some("compress, deflate")));
+        assert_eq!(Some(ContentEncoding::Gzip), negotiate(Some("compress, , gzip;level=9")));
+        assert_eq!(None, negotiate(Some("gzip-ish")));
+    }
+
+    #[cfg(all(embed, feature = "compression"))]
+    #[test]
+    fn respond_with_negotiated_encoding() {
+        use crate::{
+            rocket::{
+                http::{Header, Status},
+                local::blocking::Client,
+            },
+            static_resources_initializer, EmbeddedFileServer, ResourceMode,
+        };
+
+        let rocket = rocket::build()
+            .attach(
+                static_resources_initializer!(
+                    "README.html" => "examples/front-end/html/README.html",
+                )
+                .mode(ResourceMode::Embed),
+            )
+            .mount("/", EmbeddedFileServer::new());
+
+        let client = Client::untracked(rocket).unwrap();
+
+        let response =
+            client.get("/README.html").header(Header::new("Accept-Encoding", "gzip")).dispatch();
+
+        assert_eq!(Status::Ok, response.status());
+        assert_eq!(Some("gzip"), response.headers().get_one("Content-Encoding"));
+        assert_eq!(Some("Accept-Encoding"), response.headers().get_one("Vary"));
+
+        let response = client
+            .get("/README.html")
+            .header(Header::new("Accept-Encoding", "gzip;q=0"))
+            .dispatch();
+
+        assert_eq!(Status::Ok, response.status());
+        assert_eq!(None, response.headers().get_one("Content-Encoding"));
+        assert_eq!(Some("Accept-Encoding"), response.headers().get_one("Vary"));
+    }
+}
@@ -1,9 +1,11 @@
-use std::sync::{Mutex, MutexGuard, PoisonError};
-
 use super::{FileResources, StaticContextManager, StaticResponse};
-use crate::rocket::{
-    fairing::{Fairing, Info, Kind},
-    Build, Rocket,
+use crate::{
+    fairing::FairingOptions,
+    rocket::{
+        fairing::{Fairing, Info, Kind},
+        Build, Rocket,
+    },
+    ResourceMode,
 };
 
 const FAIRING_NAME: &str = "Static Resources (Debug)";
@@ -11,7 +13,8 @@ const FAIRING_NAME: &str = "Static Resources (Debug)";
 /// The fairing of `StaticResponse`.
 pub struct StaticResponseFairing {
     #[allow(clippy::type_complexity)]
-    pub(crate) custom_callback: Box<dyn Fn(&mut MutexGuard<FileResources>) + Send + Sync + 'static>,
+    pub(crate) custom_callback: Box<dyn Fn(&mut FileResources) + Send + Sync + 'static>,
+    pub(crate) options:         FairingOptions,
 }
 
 #[rocket::async_trait]
@@ -25,24 +28,53 @@ impl Fairing for StaticResponseFairing {
 
     #[inline]
     async fn on_ignite(&self, rocket: Rocket<Build>) -> Result<Rocket<Build>, Rocket<Build>> {
-        let resources = Mutex::new(FileResources::new());
+        // only one mode is compiled in, so this fails if another one is selected
+        let checked =
+            ResourceMode::select(self.options.mode, &rocket).and_then(|_| self.options.check());
+
+        if let Err(message) = checked {
+            rocket::error!("{}", message);
+
+            return Err(rocket);
+        }
+
+        let mut resources = FileResources::new();
 
-        (self.custom_callback)(&mut resources.lock().unwrap_or_else(PoisonError::into_inner));
+        (self.custom_callback)(&mut resources);
 
-        let state = StaticContextManager::new(resources);
+        if let Err(message) = self
+            .options
+            .substitute(&rocket, |name, substitutions| resources.substitute(name, substitutions))
+        {
+            rocket::error!("{}", message);
 
-        Ok(rocket.manage(state))
+            return Err(rocket);
+        }
+
+        let state = StaticContextManager::new(
+            resources,
+            self.options.fingerprint_base.clone(),
+            self.options.live_reload_url.as_deref(),
+            self.options.rewrite_base.clone(),
+        );
+
+        let rocket = rocket.manage(state);
+
+        Ok(self.options.mount(rocket, ResourceMode::HotReload))
     }
 }
 
 impl StaticResponse {
     #[inline]
     /// Create the fairing of `HandlebarsResponse`.
-    pub fn fairing<F>(f: F) -> impl Fairing
+    pub fn fairing<F>(f: F) -> StaticResponseFairing
     where
-        F: Fn(&mut MutexGuard<FileResources>) + Send + Sync + 'static, {
+        F: Fn(&mut FileResources) + Send + Sync + 'static, {
         StaticResponseFairing {
-            custom_callback: Box::new(f)
+            custom_callback: Box::new(f),
+            options:         FairingOptions::default(),
         }
     }
 }
+
+crate::fairing::impl_fairing_options!();
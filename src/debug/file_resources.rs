@@ -1,159 +1,549 @@
 use std::{
-    collections::HashMap,
-    fs,
-    io::{self, ErrorKind},
+    fs, io,
     path::PathBuf,
-    sync::Arc,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex, PoisonError, RwLock,
+    },
     time::SystemTime,
 };
 
 use mime::Mime;
+#[cfg(feature = "watch")]
+use rocket::tokio::sync::Notify;
+use rocket::tokio::{sync::broadcast, time::Interval};
+
+#[cfg(feature = "watch")]
+use super::watcher::Watcher;
+use crate::{
+    functions::{compute_content_digest, compute_data_etag, compute_integrity},
+    mime,
+    substitution::Substitutions,
+    table::ResourceTable,
+    EntityTag, ResourceError, ResourceKey, ResourceMetadata,
+};
 
-use crate::{functions::compute_data_etag, mime, EntityTag};
-
+/// A snapshot of a file, or data registered at runtime (without a path). It is never modified, a reload replaces it.
 #[derive(Debug)]
-struct Resource {
-    path:  PathBuf,
+pub(crate) struct Resource {
+    pub(crate) path:           Option<PathBuf>,
     // mime could be an atom `Mime`, so just clone it
-    mime:  Mime,
-    data:  Arc<Vec<u8>>,
-    etag:  EntityTag<'static>,
-    mtime: Option<SystemTime>,
+    pub(crate) mime:           Mime,
+    pub(crate) data:           Arc<Vec<u8>>,
+    pub(crate) etag:           EntityTag<'static>,
+    pub(crate) integrity:      String,
+    pub(crate) content_digest: String,
+    pub(crate) mtime:          Option<SystemTime>,
+    pub(crate) metadata:       ResourceMetadata,
+    // applied again every time the file is reloaded
+    substitutions:             Option<Arc<Substitutions>>,
+}
+
+impl Resource {
+    fn load(
+        path: PathBuf,
+        mime: Mime,
+        metadata: ResourceMetadata,
+        substitutions: Option<Arc<Substitutions>>,
+    ) -> Result<Resource, io::Error> {
+        let mtime = path.metadata()?.modified().ok();
+
+        let mut data = fs::read(&path)?;
+
+        if let Some(substitutions) = substitutions.as_ref() {
+            data = substitutions
+                .apply(&data)
+                .map_err(|message| io::Error::new(io::ErrorKind::InvalidData, message))?;
+        }
+
+        let etag = compute_data_etag(&data);
+
+        Ok(Resource {
+            path: Some(path),
+            mime,
+            etag,
+            integrity: compute_integrity(&data),
+            content_digest: compute_content_digest(&data),
+            data: Arc::new(data),
+            mtime,
+            metadata,
+            substitutions,
+        })
+    }
+
+    #[inline]
+    fn owned(mime: Mime, data: Vec<u8>) -> Resource {
+        let etag = compute_data_etag(&data);
+
+        Resource {
+            path: None,
+            mime,
+            etag,
+            integrity: compute_integrity(&data),
+            content_digest: compute_content_digest(&data),
+            data: Arc::new(data),
+            mtime: Some(SystemTime::now()),
+            metadata: ResourceMetadata::NONE,
+            substitutions: None,
+        }
+    }
+
+    /// Replace the placeholders in the data, and remember the substitutions so that they are applied again after reloading.
+    fn substitute(&self, substitutions: Arc<Substitutions>) -> Result<Resource, String> {
+        let data = substitutions.apply(&self.data)?;
+
+        Ok(Resource {
+            substitutions: Some(substitutions),
+            ..self.with_data(data)
+        })
+    }
+
+    /// Create a snapshot which has the other data, e.g. with rewritten references.
+    pub(crate) fn with_data(&self, data: Vec<u8>) -> Resource {
+        Resource {
+            path:           self.path.clone(),
+            mime:           self.mime.clone(),
+            etag:           compute_data_etag(&data),
+            integrity:      compute_integrity(&data),
+            content_digest: compute_content_digest(&data),
+            data:           Arc::new(data),
+            mtime:          self.mtime,
+            metadata:       self.metadata,
+            substitutions:  self.substitutions.clone(),
+        }
+    }
+
+    #[inline]
+    fn reload(&self) -> Result<Resource, io::Error> {
+        match self.path.as_ref() {
+            Some(path) => Resource::load(
+                path.clone(),
+                self.mime.clone(),
+                self.metadata,
+                self.substitutions.clone(),
+            ),
+            None => Err(io::Error::new(io::ErrorKind::Unsupported, "not a file")),
+        }
+    }
+
+    /// Check the modification time of the file. A resource which is not from a file is never modified.
+    fn is_modified(&self) -> Result<bool, io::Error> {
+        let metadata = match self.path.as_ref() {
+            Some(path) => path.metadata()?,
+            None => return Ok(false),
+        };
+
+        Ok(match (self.mtime, metadata.modified()) {
+            (Some(mtime), Ok(new_mtime)) => new_mtime > mtime,
+            _ => true,
+        })
+    }
+}
+
+#[derive(Debug)]
+struct Entry {
+    resource:  RwLock<Arc<Resource>>,
+    // only one thread reads a changed file, the others wait for it instead of reading it again
+    reloading: Mutex<()>,
+    #[cfg(feature = "watch")]
+    dirty:     Option<Arc<AtomicBool>>,
+}
+
+impl Entry {
+    #[inline]
+    fn current(&self) -> Arc<Resource> {
+        self.resource.read().unwrap_or_else(PoisonError::into_inner).clone()
+    }
+
+    #[inline]
+    fn replace(&self, resource: Resource) {
+        *self.resource.write().unwrap_or_else(PoisonError::into_inner) = Arc::new(resource);
+    }
+
+    /// Reload the resource if its file has changed. Returns `true` if it has been reloaded.
+    fn refresh(&self) -> Result<bool, io::Error> {
+        #[cfg(feature = "watch")]
+        if let Some(dirty) = self.dirty.as_ref() {
+            if !dirty.load(Ordering::Acquire) {
+                return Ok(false);
+            }
+
+            let _reloading = self.reloading.lock().unwrap_or_else(PoisonError::into_inner);
+
+            // another thread may have reloaded it
+            if !dirty.swap(false, Ordering::Acquire) {
+                return Ok(false);
+            }
+
+            return match self.current().reload() {
+                Ok(resource) => {
+                    self.replace(resource);
+
+                    Ok(true)
+                },
+                Err(err) => {
+                    // try again next time
+                    dirty.store(true, Ordering::Release);
+
+                    Err(err)
+                },
+            };
+        }
+
+        if !self.current().is_modified()? {
+            return Ok(false);
+        }
+
+        let _reloading = self.reloading.lock().unwrap_or_else(PoisonError::into_inner);
+
+        let current = self.current();
+
+        // another thread may have reloaded it
+        if !current.is_modified()? {
+            return Ok(false);
+        }
+
+        self.replace(current.reload()?);
+
+        Ok(true)
+    }
+}
+
+/// How long to wait after a watched file changes before reloading it.
+#[cfg(feature = "watch")]
+const SETTLE_DELAY: std::time::Duration = std::time::Duration::from_millis(50);
+
+type Entries = Arc<RwLock<ResourceTable<Arc<Entry>>>>;
+
+/// Reload the resources if needed, and broadcast the names of the reloaded ones. All of the resources are checked even if some of them fail to reload, and the first error is returned.
+fn reload_entries(
+    resources: &RwLock<ResourceTable<Arc<Entry>>>,
+    reloaded: &broadcast::Sender<&'static str>,
+) -> Result<(), io::Error> {
+    // do not block registering while reading files
+    let entries = resources
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .iter()
+        .map(|(name, entry)| (name, entry.clone()))
+        .collect::<Vec<_>>();
+
+    let mut result = Ok(());
+
+    for (name, entry) in entries {
+        match entry.refresh() {
+            Ok(true) => {
+                // nobody may be listening
+                let _ = reloaded.send(name);
+            },
+            Ok(false) => (),
+            Err(err) => {
+                if result.is_ok() {
+                    result = Err(err);
+                }
+            },
+        }
+    }
+
+    result
+}
+
+/// Reloads the resources in the background, outside of the requests, so that the subscribers are notified without waiting for a request.
+#[derive(Debug, Clone)]
+pub(crate) struct Reloader {
+    resources: Entries,
+    reloaded:  broadcast::Sender<&'static str>,
+    #[cfg(feature = "watch")]
+    changed:   Option<Arc<Notify>>,
+}
+
+impl Reloader {
+    #[inline]
+    pub(crate) fn has_subscribers(&self) -> bool {
+        self.reloaded.receiver_count() > 0
+    }
+
+    /// Wait until a watched file changes, or for the next tick of `interval` if the files are not watched.
+    #[inline]
+    pub(crate) async fn wait(&self, interval: &mut Interval) {
+        #[cfg(feature = "watch")]
+        if let Some(changed) = self.changed.as_ref() {
+            changed.notified().await;
+
+            // saving a file usually causes several events, so let them settle and reload once
+            return rocket::tokio::time::sleep(SETTLE_DELAY).await;
+        }
+
+        interval.tick().await;
+    }
+
+    #[inline]
+    pub(crate) fn reload_if_needed(&self) -> Result<(), io::Error> {
+        reload_entries(&self.resources, &self.reloaded)
+    }
 }
 
 #[derive(Debug)]
 /// Reloadable file resources.
+///
+/// The resources can be shared between threads without an outer lock. Requests for a resource which has not changed never block each other, and reloading a file only blocks the requests for that file.
+///
+/// With the `watch` feature enabled, the files are watched by the file system notification mechanism of the OS in the background, so a resource which has not changed is served without checking its file. Otherwise, the modification time of a file is checked every time the resource is requested.
 pub struct FileResources {
-    resources: HashMap<&'static str, Resource>,
+    resources:      Entries,
+    reloaded:       broadcast::Sender<&'static str>,
+    reloader_taken: AtomicBool,
+    #[cfg(feature = "watch")]
+    watcher:        Option<Mutex<Watcher>>,
 }
 
 impl FileResources {
     /// Create an instance of `FileResources`.
     #[inline]
     pub fn new() -> FileResources {
+        let (reloaded, _) = broadcast::channel(16);
+
+        // fall back to checking modification times if the watcher is unavailable
+        #[cfg(feature = "watch")]
+        let watcher = Watcher::new().ok().map(Mutex::new);
+
         FileResources {
-            resources: HashMap::new()
+            resources: Arc::new(RwLock::new(ResourceTable::new())),
+            reloaded,
+            reloader_taken: AtomicBool::new(false),
+            #[cfg(feature = "watch")]
+            watcher,
+        }
+    }
+
+    /// Subscribe to the names of the resources which are reloaded from then on.
+    #[inline]
+    pub(crate) fn subscribe(&self) -> broadcast::Receiver<&'static str> {
+        self.reloaded.subscribe()
+    }
+
+    /// Get the reloader of the resources, only the first time, so that only one reloader runs however many subscribers there are.
+    #[inline]
+    pub(crate) fn take_reloader(&self) -> Option<Reloader> {
+        if self.reloader_taken.swap(true, Ordering::AcqRel) {
+            return None;
         }
+
+        Some(Reloader {
+            resources:                         self.resources.clone(),
+            reloaded:                          self.reloaded.clone(),
+            #[cfg(feature = "watch")]
+            changed:                           self
+                .watcher
+                .as_ref()
+                .map(|watcher| watcher.lock().unwrap_or_else(PoisonError::into_inner).changed()),
+        })
     }
 
     /// Register a resource from a path and it can be reloaded automatically.
     #[inline]
     pub fn register_resource_file<P: Into<PathBuf>>(
-        &mut self,
+        &self,
         name: &'static str,
         file_path: P,
     ) -> Result<(), io::Error> {
-        let path = file_path.into();
-
-        let metadata = path.metadata()?;
-
-        let mtime = metadata.modified().ok();
-
-        let data = fs::read(&path)?;
+        self.register_resource_file_with_metadata(name, file_path, ResourceMetadata::NONE)
+    }
 
-        let etag = compute_data_etag(&data);
+    /// Register a resource from a path along with its metadata, and it can be reloaded automatically. The MIME type in the metadata overrides the one guessed from the extension of the file.
+    pub fn register_resource_file_with_metadata<P: Into<PathBuf>>(
+        &self,
+        name: &'static str,
+        file_path: P,
+        metadata: ResourceMetadata,
+    ) -> Result<(), io::Error> {
+        let path = file_path.into();
 
-        let mime = match path.extension() {
-            Some(extension) => match extension.to_str() {
-                Some(extension) => mime_guess::from_ext(extension).first_or_octet_stream(),
+        // start watching before reading, so that no change is missed
+        #[cfg(feature = "watch")]
+        let dirty = self.watcher.as_ref().and_then(|watcher| {
+            watcher.lock().unwrap_or_else(PoisonError::into_inner).watch(&path).ok()
+        });
+
+        let mime = match metadata.mime().and_then(|mime| mime.parse().ok()) {
+            Some(mime) => mime,
+            None => match path.extension() {
+                Some(extension) => match extension.to_str() {
+                    Some(extension) => mime_guess::from_ext(extension).first_or_octet_stream(),
+                    None => mime::APPLICATION_OCTET_STREAM,
+                },
                 None => mime::APPLICATION_OCTET_STREAM,
             },
-            None => mime::APPLICATION_OCTET_STREAM,
         };
 
-        let resource = Resource {
-            path,
-            mime,
-            data: Arc::new(data),
-            etag,
-            mtime,
+        let resource = match Resource::load(path, mime, metadata, None) {
+            Ok(resource) => resource,
+            Err(err) => {
+                #[cfg(feature = "watch")]
+                self.unwatch(dirty.as_ref());
+
+                return Err(err);
+            },
         };
 
-        self.resources.insert(name, resource);
+        self.insert(name, Entry {
+            resource: RwLock::new(Arc::new(resource)),
+            reloading: Mutex::new(()),
+            #[cfg(feature = "watch")]
+            dirty,
+        });
 
         Ok(())
     }
 
-    /// Unregister a resource from a file by a name.
+    /// Register a resource whose data is owned, e.g. a `Vec<u8>`, a `String`, an `Arc<[u8]>` or a `Bytes`, like `StaticResources::register_resource_owned`. The data is copied, and it is never reloaded. Its modification time is the time when it is registered.
     #[inline]
-    pub fn unregister_resource_file<S: AsRef<str>>(&mut self, name: S) -> Option<PathBuf> {
-        let name = name.as_ref();
+    pub fn register_resource_owned<D: AsRef<[u8]> + Send + Sync + 'static>(
+        &self,
+        name: &'static str,
+        mime: Mime,
+        data: D,
+    ) {
+        self.insert(name, Entry {
+            resource:                        RwLock::new(Arc::new(Resource::owned(
+                mime,
+                data.as_ref().to_vec(),
+            ))),
+            reloading:                       Mutex::new(()),
+            #[cfg(feature = "watch")]
+            dirty:                           None,
+        });
+    }
 
-        self.resources.remove(name).map(|resource| resource.path)
+    /// Register a resource whose data is produced by `f`, which is called once, when the resource is registered (the fairing registers resources when Rocket ignites).
+    #[inline]
+    pub fn register_resource_generated<D: AsRef<[u8]> + Send + Sync + 'static, F: FnOnce() -> D>(
+        &self,
+        name: &'static str,
+        mime: Mime,
+        f: F,
+    ) {
+        self.register_resource_owned(name, mime, f());
     }
 
-    /// Reload resources if needed.
     #[inline]
-    pub fn reload_if_needed(&mut self) -> Result<(), io::Error> {
-        for resource in self.resources.values_mut() {
-            let metadata = resource.path.metadata()?;
+    fn insert(&self, name: &'static str, entry: Entry) {
+        let _old_entry = self
+            .resources
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(name, Arc::new(entry));
+
+        #[cfg(feature = "watch")]
+        self.unwatch(_old_entry.as_ref().and_then(|entry| entry.dirty.as_ref()));
+    }
 
-            let (reload, new_mtime) = match resource.mtime {
-                Some(mtime) => match metadata.modified() {
-                    Ok(new_mtime) => (new_mtime > mtime, Some(new_mtime)),
-                    Err(_) => (true, None),
-                },
-                None => match metadata.modified() {
-                    Ok(new_mtime) => (true, Some(new_mtime)),
-                    Err(_) => (true, None),
-                },
-            };
+    #[cfg(feature = "watch")]
+    #[inline]
+    fn unwatch(&self, dirty: Option<&Arc<AtomicBool>>) {
+        if let (Some(watcher), Some(dirty)) = (self.watcher.as_ref(), dirty) {
+            watcher.lock().unwrap_or_else(PoisonError::into_inner).unwatch(dirty);
+        }
+    }
 
-            if reload {
-                let new_data = fs::read(&resource.path)?;
+    /// Unregister a resource by a name. The path of its file is returned, which is `None` if there is no such resource or it is not from a file.
+    #[inline]
+    pub fn unregister_resource_file<S: AsRef<str>>(&self, name: S) -> Option<PathBuf> {
+        let name = name.as_ref();
 
-                let new_etag = compute_data_etag(&new_data);
+        let entry = self.resources.write().unwrap_or_else(PoisonError::into_inner).remove(name)?;
 
-                resource.data = Arc::new(new_data);
+        #[cfg(feature = "watch")]
+        self.unwatch(entry.dirty.as_ref());
 
-                resource.etag = new_etag;
+        entry.current().path.clone()
+    }
 
-                resource.mtime = new_mtime;
-            }
-        }
+    /// Replace the placeholders in a resource, now and every time it is reloaded.
+    pub(crate) fn substitute(
+        &self,
+        name: &str,
+        substitutions: &Arc<Substitutions>,
+    ) -> Result<(), String> {
+        let entry = self
+            .resources
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(name)
+            .map(|(_, entry)| entry.clone())
+            .ok_or_else(|| format!("The resource {:?} to substitute is not registered.", name))?;
+
+        let _reloading = entry.reloading.lock().unwrap_or_else(PoisonError::into_inner);
+
+        let resource = entry
+            .current()
+            .substitute(substitutions.clone())
+            .map_err(|message| format!("Cannot substitute the resource {:?}. {}", name, message))?;
+
+        entry.replace(resource);
 
         Ok(())
     }
 
-    #[allow(clippy::type_complexity)]
-    /// Get the specific resource.
+    /// Get the names of the registered resources, in the order of registration.
     #[inline]
-    pub fn get_resource<S: AsRef<str>>(
-        &mut self,
-        name: S,
-    ) -> Result<(Mime, Arc<Vec<u8>>, &EntityTag<'static>), io::Error> {
-        let name = name.as_ref();
+    pub fn names(&self) -> Vec<&'static str> {
+        self.resources
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .iter()
+            .map(|(name, _)| name)
+            .collect()
+    }
 
-        let resource = self.resources.get_mut(name).ok_or_else(|| {
-            io::Error::new(ErrorKind::NotFound, format!("The name `{}` is not found.", name))
-        })?;
+    /// Check whether a resource is registered with the key.
+    #[inline]
+    pub(crate) fn contains<K: ResourceKey + ?Sized>(&self, key: &K) -> bool {
+        self.resources.read().unwrap_or_else(PoisonError::into_inner).contains(key)
+    }
+
+    /// Reload resources if needed. All of the resources are checked even if some of them fail to reload, and the first error is returned.
+    #[inline]
+    pub fn reload_if_needed(&self) -> Result<(), io::Error> {
+        reload_entries(&self.resources, &self.reloaded)
+    }
 
-        let metadata = resource.path.metadata()?;
+    /// Get the specific resource.
+    #[inline]
+    pub fn get_resource<K: ResourceKey>(
+        &self,
+        key: K,
+    ) -> Result<(Mime, Arc<Vec<u8>>, EntityTag<'static>), io::Error> {
+        let resource = self.get_resource_entry(&key)?;
 
-        let (reload, new_mtime) = match resource.mtime {
-            Some(mtime) => match metadata.modified() {
-                Ok(new_mtime) => (new_mtime > mtime, Some(new_mtime)),
-                Err(_) => (true, None),
-            },
-            None => match metadata.modified() {
-                Ok(new_mtime) => (true, Some(new_mtime)),
-                Err(_) => (true, None),
-            },
-        };
+        Ok((resource.mime.clone(), resource.data.clone(), resource.etag.clone()))
+    }
 
-        if reload {
-            let new_data = fs::read(&resource.path)?;
+    /// Get the snapshot of the specific resource after reloading it if needed.
+    pub(crate) fn get_resource_entry<K: ResourceKey + ?Sized>(
+        &self,
+        key: &K,
+    ) -> Result<Arc<Resource>, ResourceError> {
+        let (name, entry) = {
+            let resources = self.resources.read().unwrap_or_else(PoisonError::into_inner);
 
-            let new_etag = compute_data_etag(&new_data);
+            let (name, entry) =
+                resources.get(key).ok_or_else(|| ResourceError::not_found(key.name()))?;
 
-            resource.data = Arc::new(new_data);
+            (name, entry.clone())
+        };
 
-            resource.etag = new_etag;
+        let reloaded = entry.refresh().map_err(|source| ResourceError::Io {
+            name: name.to_string(),
+            source,
+        })?;
 
-            resource.mtime = new_mtime;
+        if reloaded {
+            // nobody may be listening
+            let _ = self.reloaded.send(name);
         }
 
-        Ok((resource.mime.clone(), resource.data.clone(), &resource.etag))
+        Ok(entry.current())
     }
 }
 
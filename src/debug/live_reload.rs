@@ -0,0 +1,126 @@
+use std::time::Duration;
+
+use super::file_resources::Reloader;
+use crate::{
+    rocket::{
+        http::{Method, Status},
+        response::stream::{stream, Event, EventStream},
+        route::{Handler, Outcome, Route},
+        tokio::{self, select, sync::broadcast::error::RecvError, task, time},
+        Data, Request, Shutdown,
+    },
+    StaticContextManager,
+};
+
+/// How often the modification times of the files are checked while a browser is listening, if the files are not watched.
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// The script which reloads the page when a resource is reloaded, or when the server comes back after the connection is lost (e.g. the application is restarted). `EventSource` reconnects by itself.
+const SCRIPT_TEMPLATE: &str = r#"<script>(function(){var l=false,s=new EventSource("{url}");s.addEventListener("reload",function(){location.reload()});s.onerror=function(){l=true};s.onopen=function(){if(l)location.reload()}})();</script>"#;
+
+/// Create the script which connects to the live-reload endpoint at `url`.
+pub(crate) fn script(url: &str) -> String {
+    let mut escaped_url = String::with_capacity(url.len());
+
+    // the URL is put in a JavaScript string inside a `<script>` element
+    for c in url.chars() {
+        match c {
+            c if matches!(c, '"' | '\\' | '<' | '>' | '&') || c.is_control() => {
+                escaped_url.push_str(&format!("\\u{:04x}", c as u32))
+            },
+            c => escaped_url.push(c),
+        }
+    }
+
+    SCRIPT_TEMPLATE.replace("{url}", &escaped_url)
+}
+
+/// Insert a script before the last `</body>` of an HTML document, or append it if there is no such tag.
+pub(crate) fn inject(html: &[u8], script: &str) -> Vec<u8> {
+    const END_TAG: &[u8] = b"</body";
+
+    let index = html
+        .windows(END_TAG.len())
+        .rposition(|window| window.eq_ignore_ascii_case(END_TAG))
+        .unwrap_or(html.len());
+
+    let mut data = Vec::with_capacity(html.len() + script.len());
+
+    data.extend_from_slice(&html[..index]);
+    data.extend_from_slice(script.as_bytes());
+    data.extend_from_slice(&html[index..]);
+
+    data
+}
+
+/// Sends a `reload` event, whose data is the name of the resource, every time a resource is reloaded.
+#[derive(Clone)]
+struct LiveReloadHandler;
+
+#[rocket::async_trait]
+impl Handler for LiveReloadHandler {
+    async fn handle<'r>(&self, request: &'r Request<'_>, data: Data<'r>) -> Outcome<'r> {
+        let resources = match request
+            .rocket()
+            .state::<StaticContextManager>()
+            .and_then(|manager| manager.file_resources())
+        {
+            Some(resources) => resources,
+            None => return Outcome::forward(data, Status::NotFound),
+        };
+
+        let mut shutdown = request.rocket().shutdown();
+
+        let mut receiver = resources.subscribe();
+
+        // resources are reloaded lazily, so one task reloads them for every browser
+        if let Some(reloader) = resources.take_reloader() {
+            spawn_reloader(reloader, shutdown.clone());
+        }
+
+        let events = stream! {
+            loop {
+                select! {
+                    name = receiver.recv() => match name {
+                        Ok(name) => yield Event::data(name).event("reload"),
+                        // some names are missed, but one event is enough to reload the page
+                        Err(RecvError::Lagged(_)) => yield Event::empty().event("reload"),
+                        Err(RecvError::Closed) => break,
+                    },
+                    _ = &mut shutdown => break,
+                }
+            }
+        };
+
+        Outcome::from(request, EventStream::from(events))
+    }
+}
+
+/// Reload the resources when their files change while any browser is listening, until Rocket shuts down.
+fn spawn_reloader(reloader: Reloader, mut shutdown: Shutdown) {
+    tokio::spawn(async move {
+        let mut interval = time::interval(POLL_INTERVAL);
+
+        loop {
+            select! {
+                _ = reloader.wait(&mut interval) => (),
+                _ = &mut shutdown => break,
+            }
+
+            if !reloader.has_subscribers() {
+                continue;
+            }
+
+            let reloader = reloader.clone();
+
+            // a file which cannot be read now is reported when it is requested
+            let _ = task::spawn_blocking(move || reloader.reload_if_needed()).await;
+        }
+    });
+}
+
+/// The route of the live-reload endpoint.
+#[inline]
+pub(crate) fn routes() -> Vec<Route> {
+    vec![Route::new(Method::Get, "/", LiveReloadHandler)]
+}
@@ -1,9 +1,19 @@
-/// Used in the fairing of `StaticResponse` to include static files into your executable binary file. You need to specify each file's name and its path relative to the directory containing the manifest of your package. In order to reduce the compilation time and allow to hot-reload resources, files are compiled into your executable binary file together, only when you are using the **release** profile.
+/// Used in the fairing of `StaticResponse` to include static files into your executable binary file. You need to specify each file's name and its path relative to the directory containing the manifest of your package. In order to reduce the compilation time and allow to hot-reload resources, files are compiled into your executable binary file together, only when you are using the **release** profile or the `embed` feature.
 #[macro_export]
 macro_rules! static_resources_initialize {
-    ( $resources:expr, $($name:expr => $path:expr), * $(,)* ) => {
+    ( $resources:expr, $($name:expr => $path:expr $(; { $($metadata:tt)* })?), * $(,)* ) => {
+        $crate::rocket_include_static_resources_macros::check_unique_names!($($name),*);
+
         $(
-            $resources.register_resource_file($name, $crate::manifest_dir_macros::not_directory_path!($path)).unwrap();
+            $resources.register_resource_file_with_metadata($name, $crate::manifest_dir_macros::not_directory_path!($path), $crate::rocket_include_static_resources_macros::resource_metadata!($crate, $path $(, { $($metadata)* })?)).unwrap();
         )*
     };
 }
+
+/// Used in the fairing of `StaticResponse` like `static_resources_initialize!`. The files are not included, so there is no table to generate in the hot-reload mode.
+#[macro_export]
+macro_rules! static_resources_initialize_table {
+    ( $resources:expr, $($name:expr => $path:expr $(; { $($metadata:tt)* })?), * $(,)* ) => {
+        $crate::static_resources_initialize!($resources, $($name => $path $(; { $($metadata)* })?),*);
+    };
+}
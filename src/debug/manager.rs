@@ -1,49 +1,381 @@
-use std::sync::{Mutex, PoisonError};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, PoisonError},
+};
 
-use super::FileResources;
-use crate::{EtagIfNoneMatch, StaticResponse};
+use super::{file_resources, live_reload, FileResources, StaticResponse};
+use crate::{fingerprint, rewrite, Preconditions, Resource, ResourceError, ResourceKey};
+
+/// A resource whose references are rewritten, along with what it depends on.
+#[derive(Debug, Clone)]
+struct Rewritten {
+    source:     Arc<file_resources::Resource>,
+    // the names and the tags of the entity tags of the referenced resources
+    references: Vec<(String, String)>,
+    resource:   Arc<file_resources::Resource>,
+}
+
+/// Rewrites the references in HTML and CSS resources to fingerprinted URLs. The results are kept until the resources or the resources which they refer to change.
+#[derive(Debug)]
+struct Rewriter {
+    base:  String,
+    cache: Mutex<HashMap<String, Rewritten>>,
+}
+
+/// An HTML resource with the live-reload script.
+#[derive(Debug)]
+struct Injected {
+    source:   Arc<file_resources::Resource>,
+    resource: Arc<file_resources::Resource>,
+}
+
+/// Injects the live-reload script into HTML resources. The results are kept until the resources change.
+#[derive(Debug)]
+struct Injector {
+    script: String,
+    cache:  Mutex<HashMap<String, Injected>>,
+}
 
 /// To monitor the state of static resources.
 #[derive(Debug)]
 pub struct StaticContextManager {
-    pub resources: Mutex<FileResources>,
+    pub resources:    FileResources,
+    fingerprint_base: Option<String>,
+    injector:         Option<Injector>,
+    rewriter:         Option<Rewriter>,
 }
 
 impl StaticContextManager {
     #[inline]
-    pub(crate) fn new(resources: Mutex<FileResources>) -> StaticContextManager {
+    pub(crate) fn new(
+        resources: FileResources,
+        fingerprint_base: Option<String>,
+        live_reload_url: Option<&str>,
+        rewrite_base: Option<String>,
+    ) -> StaticContextManager {
         StaticContextManager {
             resources,
+            fingerprint_base,
+            injector: live_reload_url.map(|url| Injector {
+                script: live_reload::script(url),
+                cache:  Mutex::new(HashMap::new()),
+            }),
+            rewriter: rewrite_base.map(|base| Rewriter {
+                base,
+                cache: Mutex::new(HashMap::new()),
+            }),
         }
     }
 
-    /// Build a `StaticResponse`.
+    /// Get the resources, which can be used from multiple threads at the same time.
+    #[inline]
+    pub fn resources(&self) -> &FileResources {
+        &self.resources
+    }
+
+    #[cfg(not(embed))]
+    #[inline]
+    pub(crate) fn file_resources(&self) -> Option<&FileResources> {
+        Some(&self.resources)
+    }
+
+    /// Check whether a resource is registered with the name.
+    #[inline]
+    pub fn contains<K: ResourceKey>(&self, key: K) -> bool {
+        self.resources.contains(&key)
+    }
+
+    /// Get the URL which contains the hash of the current content of a resource. `None` if fingerprinted URLs are not enabled in the fairing, or the resource cannot be loaded.
+    #[inline]
+    pub fn fingerprinted_url<K: ResourceKey>(&self, key: K) -> Option<String> {
+        let base = self.fingerprint_base.as_ref()?;
+
+        self.fingerprinted_name(key).map(|name| fingerprint::join_url(base, &name))
+    }
+
+    /// Get the names of the resources along with the fingerprinted URLs of their current contents, e.g. to write a manifest. The resources which cannot be loaded are skipped. Nothing if fingerprinted URLs are not enabled in the fairing.
+    #[inline]
+    pub fn fingerprinted_urls(&self) -> impl Iterator<Item = (&'static str, String)> + '_ {
+        let names =
+            if self.fingerprint_base.is_some() { self.resources.names() } else { Vec::new() };
+
+        names.into_iter().filter_map(|name| self.fingerprinted_url(name).map(|url| (name, url)))
+    }
+
     #[inline]
-    pub fn build<S: AsRef<str>>(
+    pub(crate) fn fingerprinted_name<K: ResourceKey>(&self, key: K) -> Option<String> {
+        self.get_resource_entry(&key)
+            .ok()
+            .map(|resource| fingerprint::fingerprinted_name(key.name(), &resource.etag))
+    }
+
+    /// Get the Subresource Integrity metadata of the current content of a resource, e.g. `sha384-...`, which can be the value of an `integrity` attribute. `None` if the resource cannot be loaded.
+    #[inline]
+    pub fn integrity<K: ResourceKey>(&self, key: K) -> Option<String> {
+        self.get_resource_entry(&key).ok().map(|resource| resource.integrity.clone())
+    }
+
+    /// Get a view of a resource after reloading it if needed.
+    #[inline]
+    pub fn get_resource<K: ResourceKey>(&self, key: K) -> Result<Resource, ResourceError> {
+        self.get_resource_entry(&key).map(|resource| {
+            Resource::from_shared(
+                resource.mime.clone(),
+                resource.data.clone(),
+                resource.etag.clone(),
+                resource.mtime,
+            )
+        })
+    }
+
+    /// Build a `StaticResponse`. The preconditions can be a `&Preconditions` or an `&EtagIfNoneMatch`, and they are evaluated when responding. If the resource cannot be built, the response is `404 Not Found` for an unknown name, or `500 Internal Server Error` for a resource which cannot be loaded, and the error is logged.
+    #[inline]
+    pub fn build<P: Into<Preconditions>, K: ResourceKey>(
         &self,
-        etag_if_none_match: &EtagIfNoneMatch<'_>,
-        name: S,
+        preconditions: P,
+        key: K,
     ) -> StaticResponse {
-        self.try_build(etag_if_none_match, name).unwrap()
+        self.try_build(preconditions, key).unwrap_or_else(StaticResponse::from)
     }
 
     /// Attempt to build a `StaticResponse`.
     #[inline]
-    pub fn try_build<S: AsRef<str>>(
-        &self,
-        etag_if_none_match: &EtagIfNoneMatch<'_>,
-        name: S,
-    ) -> Result<StaticResponse, std::io::Error> {
-        self.resources
-            .lock()
-            .unwrap_or_else(PoisonError::into_inner)
-            .get_resource(name.as_ref())
-            .map(|resource| {
-                if etag_if_none_match.weak_eq(resource.2) {
-                    StaticResponse::not_modified()
-                } else {
-                    StaticResponse::build(&resource.0, resource.1.clone(), resource.2)
-                }
-            })
+    pub fn try_build<P: Into<Preconditions>, K: ResourceKey>(
+        &self,
+        preconditions: P,
+        key: K,
+    ) -> Result<StaticResponse, ResourceError> {
+        self.get_resource_entry(&key)
+            .map(|resource| StaticResponse::build(&resource, preconditions.into()))
+    }
+
+    /// Get the snapshot of a resource after reloading it if needed, as it is served, with its references rewritten and the live-reload script injected if they are enabled. The entity tag and the digests are the ones of the served data.
+    #[inline]
+    fn get_resource_entry<K: ResourceKey + ?Sized>(
+        &self,
+        key: &K,
+    ) -> Result<Arc<file_resources::Resource>, ResourceError> {
+        let resource = self.resources.get_resource_entry(key)?;
+
+        Ok(self.prepare(key.name(), resource, &mut Vec::new()))
+    }
+
+    /// Rewrite and inject a resource. `rewriting` holds the names of the resources whose references are being rewritten, which refer to this one.
+    #[inline]
+    fn prepare(
+        &self,
+        name: &str,
+        resource: Arc<file_resources::Resource>,
+        rewriting: &mut Vec<String>,
+    ) -> Arc<file_resources::Resource> {
+        let resource = self.rewrite(name, resource, rewriting);
+
+        self.inject(name, resource)
+    }
+
+    fn inject(
+        &self,
+        name: &str,
+        resource: Arc<file_resources::Resource>,
+    ) -> Arc<file_resources::Resource> {
+        let injector = match self.injector.as_ref() {
+            Some(injector) if resource.mime.essence_str() == "text/html" => injector,
+            _ => return resource,
+        };
+
+        let mut cache = injector.cache.lock().unwrap_or_else(PoisonError::into_inner);
+
+        if let Some(injected) = cache.get(name) {
+            if Arc::ptr_eq(&injected.source, &resource) {
+                return injected.resource.clone();
+            }
+        }
+
+        let injected =
+            Arc::new(resource.with_data(live_reload::inject(&resource.data, &injector.script)));
+
+        cache.insert(name.to_string(), Injected {
+            source: resource, resource: injected.clone()
+        });
+
+        injected
+    }
+
+    fn rewrite(
+        &self,
+        name: &str,
+        resource: Arc<file_resources::Resource>,
+        rewriting: &mut Vec<String>,
+    ) -> Arc<file_resources::Resource> {
+        let (rewriter, fingerprint_base) =
+            match (self.rewriter.as_ref(), self.fingerprint_base.as_deref()) {
+                (Some(rewriter), Some(fingerprint_base)) => (rewriter, fingerprint_base),
+                _ => return resource,
+            };
+
+        let kind = match rewrite::Kind::of(&resource.mime) {
+            Some(kind) => kind,
+            None => return resource,
+        };
+
+        rewriting.push(name.to_string());
+
+        let rewritten =
+            self.rewrite_references(rewriter, fingerprint_base, name, resource, kind, rewriting);
+
+        rewriting.pop();
+
+        rewritten
+    }
+
+    fn rewrite_references(
+        &self,
+        rewriter: &Rewriter,
+        fingerprint_base: &str,
+        name: &str,
+        resource: Arc<file_resources::Resource>,
+        kind: rewrite::Kind,
+        rewriting: &mut Vec<String>,
+    ) -> Arc<file_resources::Resource> {
+        // do not hold the lock while the referenced resources are rewritten
+        let cached =
+            rewriter.cache.lock().unwrap_or_else(PoisonError::into_inner).get(name).cloned();
+
+        if let Some(cached) = cached {
+            let is_fresh = Arc::ptr_eq(&cached.source, &resource)
+                && cached.references.iter().all(|(name, tag)| {
+                    self.referenced_resource(name, rewriting)
+                        .map(|referenced| referenced.etag.get_tag() == tag)
+                        .unwrap_or(false)
+                });
+
+            if is_fresh {
+                return cached.resource;
+            }
+        }
+
+        let mut references = Vec::new();
+
+        let data = rewrite::rewrite(name, &resource.data, kind, &rewriter.base, |reference| {
+            let referenced = self.referenced_resource(reference, rewriting)?;
+
+            references.push((reference.to_string(), referenced.etag.get_tag().to_string()));
+
+            Some(fingerprint::join_url(
+                fingerprint_base,
+                &fingerprint::fingerprinted_name(reference, &referenced.etag),
+            ))
+        });
+
+        let rewritten = match data {
+            Some(data) => Arc::new(resource.with_data(data)),
+            None => resource.clone(),
+        };
+
+        rewriter.cache.lock().unwrap_or_else(PoisonError::into_inner).insert(
+            name.to_string(),
+            Rewritten {
+                source: resource,
+                references,
+                resource: rewritten.clone(),
+            },
+        );
+
+        rewritten
+    }
+
+    /// Get a resource which another one refers to. `None` if the reference should be left as it is, because the resource cannot be loaded, or it is an HTML or CSS resource whose fingerprint is not known yet (a reference which closes a cycle) or which is too deep.
+    #[inline]
+    fn referenced_resource(
+        &self,
+        name: &str,
+        rewriting: &mut Vec<String>,
+    ) -> Option<Arc<file_resources::Resource>> {
+        let resource = self.resources.get_resource_entry(name).ok()?;
+
+        if rewrite::Kind::of(&resource.mime).is_some()
+            && (rewriting.len() >= rewrite::MAX_DEPTH || rewriting.iter().any(|n| n == name))
+        {
+            return None;
+        }
+
+        Some(self.prepare(name, resource, rewriting))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        functions::{compute_content_digest, compute_data_etag, compute_integrity},
+        rocket::{http::Status, local::blocking::Client},
+        EmbeddedFileServer, ResourceMode, StaticResponse,
+    };
+
+    #[test]
+    fn describe_injected_html() {
+        let rocket = rocket::build()
+            .attach(
+                StaticResponse::fairing(|resources| {
+                    resources.register_resource_owned(
+                        "index.html",
+                        mime::TEXT_HTML,
+                        "<html><body></body></html>",
+                    );
+                })
+                .live_reload("/live-reload")
+                .mode(ResourceMode::HotReload),
+            )
+            .mount("/", EmbeddedFileServer::new());
+
+        let client = Client::untracked(rocket).unwrap();
+
+        let response = client.get("/index.html").dispatch();
+
+        assert_eq!(Status::Ok, response.status());
+
+        let etag = response.headers().get_one("ETag").unwrap().to_string();
+        let content_digest = response.headers().get_one("Content-Digest").unwrap().to_string();
+
+        let body = response.into_bytes().unwrap();
+
+        assert!(body.windows(7).any(|window| window == b"<script"));
+        assert_eq!(compute_data_etag(&body).to_string(), etag);
+        assert_eq!(compute_content_digest(&body), content_digest);
+
+        let manager = client.rocket().state::<crate::StaticContextManager>().unwrap();
+
+        assert_eq!(Some(compute_integrity(&body)), manager.integrity("index.html"));
+
+        let response = client
+            .get("/index.html")
+            .header(rocket::http::Header::new("If-None-Match", etag))
+            .dispatch();
+
+        assert_eq!(Status::NotModified, response.status());
+    }
+
+    #[test]
+    fn leave_self_imports_unrewritten() {
+        let rocket = rocket::build()
+            .attach(
+                StaticResponse::fairing(|resources| {
+                    resources.register_resource_owned(
+                        "a.css",
+                        mime::TEXT_CSS,
+                        "@import url(\"a.css\");",
+                    );
+                })
+                .fingerprinted("/assets")
+                .rewrite_references("/")
+                .mode(ResourceMode::HotReload),
+            )
+            .mount("/", EmbeddedFileServer::new());
+
+        let client = Client::untracked(rocket).unwrap();
+
+        let response = client.get("/a.css").dispatch();
+
+        assert_eq!(Status::Ok, response.status());
+        assert_eq!(response.into_string().unwrap(), "@import url(\"a.css\");");
     }
 }
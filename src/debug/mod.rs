@@ -3,10 +3,18 @@ mod static_response;
 
 mod manager;
 
+#[cfg(not(embed))]
 mod fairing;
 
+#[cfg(not(embed))]
 mod macros;
 
+pub(crate) mod live_reload;
+
+#[cfg(feature = "watch")]
+mod watcher;
+
+#[cfg(not(embed))]
 pub use fairing::*;
 pub use file_resources::*;
 pub use manager::*;
@@ -1,66 +1,145 @@
-use std::sync::Arc;
+use std::{io::Cursor, ops::Range, sync::Arc, time::SystemTime};
 
-use mime::Mime;
 use rc_u8_reader::ArcU8Reader;
 
+use super::file_resources::Resource;
 use crate::{
+    range::{self, RangeResponse},
     rocket::{
         http::Status,
         request::Request,
         response::{self, Responder, Response},
     },
-    EntityTag,
+    EntityTag, Preconditions, ResourceError, ResourceMetadata,
 };
 
+/// A part of shared data, so that a range of a resource can be sent without copying it.
+struct ArcSlice {
+    data:  Arc<Vec<u8>>,
+    range: Range<usize>,
+}
+
+impl AsRef<[u8]> for ArcSlice {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        &self.data[self.range.clone()]
+    }
+}
+
 #[derive(Debug)]
-struct StaticResponseInner {
-    mime: String,
-    data: Arc<Vec<u8>>,
-    etag: String,
+struct Content {
+    mime:           String,
+    data:           Arc<Vec<u8>>,
+    etag:           EntityTag<'static>,
+    content_digest: String,
+    last_modified:  Option<SystemTime>,
+    metadata:       ResourceMetadata,
+    preconditions:  Preconditions,
 }
 
 #[derive(Debug)]
-/// To respond a static resource.
+/// To respond a static resource, or an error if the resource cannot be built, which is `404 Not Found` for an unknown name, or `500 Internal Server Error` for a resource which cannot be loaded.
 pub struct StaticResponse {
-    inner: Option<StaticResponseInner>,
+    content: Result<Content, ResourceError>,
 }
 
 impl StaticResponse {
+    /// Build a response of a resource.
     #[inline]
-    pub(crate) fn build(
-        mime: &Mime,
-        data: Arc<Vec<u8>>,
-        etag: &EntityTag<'static>,
-    ) -> StaticResponse {
+    pub(crate) fn build(resource: &Resource, preconditions: Preconditions) -> StaticResponse {
         StaticResponse {
-            inner: Some(StaticResponseInner {
-                mime: mime.to_string(),
-                data,
-                etag: etag.to_string(),
+            content: Ok(Content {
+                mime: resource.mime.to_string(),
+                data: resource.data.clone(),
+                etag: resource.etag.clone(),
+                content_digest: resource.content_digest.clone(),
+                last_modified: resource.mtime,
+                metadata: resource.metadata,
+                preconditions,
             }),
         }
     }
+}
 
+impl From<ResourceError> for StaticResponse {
     #[inline]
-    pub(crate) const fn not_modified() -> StaticResponse {
+    fn from(error: ResourceError) -> Self {
         StaticResponse {
-            inner: None
+            content: Err(error)
         }
     }
 }
 
 impl<'r, 'o: 'r> Responder<'r, 'o> for StaticResponse {
     #[inline]
-    fn respond_to(self, _: &'r Request<'_>) -> response::Result<'o> {
+    fn respond_to(self, request: &'r Request<'_>) -> response::Result<'o> {
+        match self.content {
+            Ok(content) => content.respond_to(request),
+            Err(error) => error.respond_to(request),
+        }
+    }
+}
+
+impl<'r, 'o: 'r> Responder<'r, 'o> for Content {
+    #[inline]
+    fn respond_to(self, request: &'r Request<'_>) -> response::Result<'o> {
         let mut response = Response::build();
 
-        if let Some(inner) = self.inner {
-            response.raw_header("Etag", inner.etag);
-            response.raw_header("Content-Type", inner.mime);
+        response.raw_header("Etag", self.etag.to_string());
+
+        self.metadata.apply_headers(&mut response);
+
+        match self.preconditions.evaluate(request.method(), &self.etag, self.last_modified) {
+            Some(status) => {
+                response.status(status);
+            },
+            None => {
+                let len = self.data.len();
+
+                if let Some(last_modified) = self.last_modified {
+                    response.raw_header("Last-Modified", httpdate::fmt_http_date(last_modified));
+                }
+
+                response.raw_header("Accept-Ranges", "bytes");
+
+                match RangeResponse::evaluate(
+                    request,
+                    &self.data,
+                    &self.mime,
+                    &self.etag,
+                    self.last_modified,
+                ) {
+                    RangeResponse::Full => {
+                        response.raw_header("Content-Type", self.mime);
+                        response.raw_header("Content-Digest", self.content_digest);
+
+                        response.sized_body(len, ArcU8Reader::new(self.data));
+                    },
+                    RangeResponse::Single(range) => {
+                        response.status(Status::PartialContent);
+                        response.raw_header("Content-Type", self.mime);
+                        response.raw_header("Content-Range", range::content_range(&range, len));
+
+                        response.sized_body(
+                            range.len(),
+                            Cursor::new(ArcSlice {
+                                data: self.data,
+                                range,
+                            }),
+                        );
+                    },
+                    RangeResponse::Multiple(content_type, body) => {
+                        response.status(Status::PartialContent);
+                        response.raw_header("Content-Type", content_type);
 
-            response.sized_body(inner.data.len(), ArcU8Reader::new(inner.data));
-        } else {
-            response.status(Status::NotModified);
+                        response.sized_body(body.len(), Cursor::new(body));
+                    },
+                    RangeResponse::Unsatisfiable => {
+                        response.status(Status::RangeNotSatisfiable);
+                        response.raw_header("Content-Range", range::unsatisfied_content_range(len));
+                    },
+                }
+            },
         }
 
         response.ok()
@@ -0,0 +1,122 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::{self, Debug, Formatter},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex, PoisonError,
+    },
+};
+
+use notify::{
+    event::{AccessKind, AccessMode},
+    Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher as _,
+};
+use rocket::tokio::sync::Notify;
+
+type DirtyFlags = Arc<Mutex<HashMap<PathBuf, Vec<Arc<AtomicBool>>>>>;
+
+/// Watches the files of resources in the background and marks them dirty when they change.
+pub(crate) struct Watcher {
+    watcher:             RecommendedWatcher,
+    dirty_flags:         DirtyFlags,
+    watched_directories: HashSet<PathBuf>,
+    changed:             Arc<Notify>,
+}
+
+impl Debug for Watcher {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_struct("Watcher").field("watched_directories", &self.watched_directories).finish()
+    }
+}
+
+impl Watcher {
+    pub(crate) fn new() -> notify::Result<Watcher> {
+        let dirty_flags = DirtyFlags::default();
+        let changed = Arc::new(Notify::new());
+
+        let flags = dirty_flags.clone();
+        let notify = changed.clone();
+
+        let watcher = notify::recommended_watcher(move |event: notify::Result<Event>| {
+            let flags = flags.lock().unwrap_or_else(PoisonError::into_inner);
+
+            match event {
+                Ok(event) => {
+                    // reading a file (including reloading it) must not make it dirty again
+                    if let EventKind::Access(kind) = event.kind {
+                        if kind != AccessKind::Close(AccessMode::Write) {
+                            return;
+                        }
+                    }
+
+                    for path in event.paths.iter() {
+                        for flag in flags.get(path).into_iter().flatten() {
+                            flag.store(true, Ordering::Release);
+                        }
+                    }
+                },
+                // some events may be lost, so everything could be changed
+                Err(_) => {
+                    for flag in flags.values().flatten() {
+                        flag.store(true, Ordering::Release);
+                    }
+                },
+            }
+
+            // the permit is kept if nobody is waiting now
+            notify.notify_one();
+        })?;
+
+        Ok(Watcher {
+            watcher,
+            dirty_flags,
+            watched_directories: HashSet::new(),
+            changed,
+        })
+    }
+
+    /// Get what is notified every time a watched file may have changed.
+    #[inline]
+    pub(crate) fn changed(&self) -> Arc<Notify> {
+        self.changed.clone()
+    }
+
+    /// Start watching a file. The returned flag becomes `true` when the file changes.
+    ///
+    /// The parent directory is watched instead of the file itself, so that files replaced by renaming (as many editors save) are still tracked.
+    pub(crate) fn watch(&mut self, path: &Path) -> notify::Result<Arc<AtomicBool>> {
+        let path = path.canonicalize()?;
+
+        if let Some(directory) = path.parent() {
+            if !self.watched_directories.contains(directory) {
+                self.watcher.watch(directory, RecursiveMode::NonRecursive)?;
+
+                self.watched_directories.insert(directory.to_path_buf());
+            }
+        }
+
+        let flag = Arc::new(AtomicBool::new(false));
+
+        self.dirty_flags
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .entry(path)
+            .or_default()
+            .push(flag.clone());
+
+        Ok(flag)
+    }
+
+    /// Stop tracking a flag returned by `watch`.
+    pub(crate) fn unwatch(&mut self, flag: &Arc<AtomicBool>) {
+        let mut dirty_flags = self.dirty_flags.lock().unwrap_or_else(PoisonError::into_inner);
+
+        for flags in dirty_flags.values_mut() {
+            flags.retain(|f| !Arc::ptr_eq(f, flag));
+        }
+
+        dirty_flags.retain(|_, flags| !flags.is_empty());
+    }
+}
@@ -0,0 +1,107 @@
+use super::{SelectedResources, StaticContextManager, StaticResponse};
+use crate::{
+    fairing::FairingOptions,
+    release::overlay,
+    rocket::{
+        fairing::{Fairing, Info, Kind},
+        Build, Orbit, Rocket,
+    },
+    ResourceMode,
+};
+
+const FAIRING_NAME: &str = "Static Resources";
+
+/// The fairing of `StaticResponse`.
+pub struct StaticResponseFairing {
+    #[allow(clippy::type_complexity)]
+    pub(crate) custom_callback: Box<dyn Fn(&mut SelectedResources) + Send + Sync + 'static>,
+    pub(crate) options:         FairingOptions,
+}
+
+#[rocket::async_trait]
+impl Fairing for StaticResponseFairing {
+    #[inline]
+    fn info(&self) -> Info {
+        Info {
+            name: FAIRING_NAME, kind: Kind::Ignite | Kind::Liftoff
+        }
+    }
+
+    #[inline]
+    async fn on_ignite(&self, rocket: Rocket<Build>) -> Result<Rocket<Build>, Rocket<Build>> {
+        let mode = match ResourceMode::select(self.options.mode, &rocket)
+            .and_then(|mode| self.options.check().map(|_| mode))
+        {
+            Ok(mode) => mode,
+            Err(message) => {
+                rocket::error!("{}", message);
+
+                return Err(rocket);
+            },
+        };
+
+        let mut resources = SelectedResources::new(mode);
+
+        (self.custom_callback)(&mut resources);
+
+        if let Err(message) = self
+            .options
+            .substitute(&rocket, |name, substitutions| resources.substitute(name, substitutions))
+        {
+            rocket::error!("{}", message);
+
+            return Err(rocket);
+        }
+
+        if let SelectedResources::Embedded(resources) = &mut resources {
+            if let (Some(base), Some(fingerprint_base)) =
+                (self.options.rewrite_base.as_deref(), self.options.fingerprint_base.as_deref())
+            {
+                resources.rewrite_references(base, fingerprint_base);
+            }
+
+            if let Err(message) = overlay::configure(&rocket, resources) {
+                rocket::error!("{}", message);
+
+                return Err(rocket);
+            }
+        }
+
+        let state = StaticContextManager::new(
+            resources,
+            self.options.fingerprint_base.clone(),
+            self.options.live_reload_url.as_deref(),
+            self.options.rewrite_base.clone(),
+        );
+
+        let rocket = rocket.manage(state);
+
+        Ok(self.options.mount(rocket, mode))
+    }
+
+    #[inline]
+    async fn on_liftoff(&self, rocket: &Rocket<Orbit>) {
+        if let Some(overlay) = rocket
+            .state::<StaticContextManager>()
+            .and_then(|state| state.static_resources())
+            .and_then(|resources| resources.overlay())
+        {
+            overlay::watch(rocket, overlay.clone());
+        }
+    }
+}
+
+impl StaticResponse {
+    #[inline]
+    /// Create the fairing of `StaticResponse`.
+    pub fn fairing<F>(f: F) -> StaticResponseFairing
+    where
+        F: Fn(&mut SelectedResources) + Send + Sync + 'static, {
+        StaticResponseFairing {
+            custom_callback: Box::new(f),
+            options:         FairingOptions::default(),
+        }
+    }
+}
+
+crate::fairing::impl_fairing_options!();
@@ -0,0 +1,27 @@
+/// Used in the fairing of `StaticResponse` to include static files into your executable binary file and register their paths. You need to specify each file's name and its path relative to the directory containing the manifest of your package. With both the `embed` and `hot-reload` features enabled, files are always compiled into your executable binary file, and which of the included contents or the files are served depends on the mode selected when Rocket ignites. With the `compression` feature enabled, gzip, brotli and zstd representations of each file are also compressed and included at compile time.
+#[macro_export]
+macro_rules! static_resources_initialize {
+    ( $resources:expr, $($name:expr => $path:expr $(; { $($metadata:tt)* })?), * $(,)* ) => {
+        $crate::rocket_include_static_resources_macros::check_unique_names!($($name),*);
+
+        let (timestamp, http_date) = $crate::rocket_include_static_resources_macros::build_time!();
+
+        $resources.set_last_modified_precomputed(::std::time::UNIX_EPOCH + ::std::time::Duration::from_secs(timestamp), http_date);
+
+        $(
+            $resources.register_resource($name, $crate::manifest_dir_macros::not_directory_path!($path), $crate::manifest_dir_macros::mime_guess!(default = "application/octet-stream", $path), include_bytes!($crate::manifest_dir_macros::path!($path)), $crate::rocket_include_static_resources_macros::include_etag!($path), $crate::rocket_include_static_resources_macros::include_digests!($path), $crate::rocket_include_static_resources_macros::include_encoded!($crate, $path), $crate::rocket_include_static_resources_macros::resource_metadata!($crate, $path $(, { $($metadata)* })?)).unwrap();
+        )*
+    };
+}
+
+/// Used in the fairing of `StaticResponse` like `static_resources_initialize!`, but the included resources are put into a table generated at compile time, which is sorted by name. The names must be literal strings.
+#[macro_export]
+macro_rules! static_resources_initialize_table {
+    ( $resources:expr, $($name:expr => $path:expr $(; { $($metadata:tt)* })?), * $(,)* ) => {
+        let (timestamp, http_date) = $crate::rocket_include_static_resources_macros::build_time!();
+
+        $resources.set_last_modified_precomputed(::std::time::UNIX_EPOCH + ::std::time::Duration::from_secs(timestamp), http_date);
+
+        $resources.register_table($crate::rocket_include_static_resources_macros::static_table!($crate, $($name => $path $(; { $($metadata)* })?),*), &[$(($name, $crate::manifest_dir_macros::not_directory_path!($path), $crate::rocket_include_static_resources_macros::resource_metadata!($crate, $path $(, { $($metadata)* })?))),*]).unwrap();
+    };
+}
@@ -0,0 +1,158 @@
+use super::SelectedResources;
+use crate::{
+    debug::{self, FileResources},
+    release::{self, StaticResources},
+    Preconditions, Resource, ResourceError, ResourceKey, ResourceMode, StaticResponse,
+};
+
+#[derive(Debug)]
+enum Manager {
+    Embed(Box<release::StaticContextManager>),
+    HotReload(Box<debug::StaticContextManager>),
+}
+
+/// To monitor the state of static resources.
+#[derive(Debug)]
+pub struct StaticContextManager {
+    manager: Manager,
+}
+
+impl StaticContextManager {
+    #[inline]
+    pub(crate) fn new(
+        resources: SelectedResources,
+        fingerprint_base: Option<String>,
+        live_reload_url: Option<&str>,
+        rewrite_base: Option<String>,
+    ) -> StaticContextManager {
+        let manager = match resources {
+            SelectedResources::Embedded(resources) => Manager::Embed(Box::new(
+                release::StaticContextManager::new(resources, fingerprint_base),
+            )),
+            SelectedResources::Files(resources) => {
+                Manager::HotReload(Box::new(debug::StaticContextManager::new(
+                    resources,
+                    fingerprint_base,
+                    live_reload_url,
+                    rewrite_base,
+                )))
+            },
+        };
+
+        StaticContextManager {
+            manager,
+        }
+    }
+
+    /// Get the mode selected when Rocket ignited.
+    #[inline]
+    pub fn mode(&self) -> ResourceMode {
+        match self.manager {
+            Manager::Embed(_) => ResourceMode::Embed,
+            Manager::HotReload(_) => ResourceMode::HotReload,
+        }
+    }
+
+    /// Get the resources if they are embedded.
+    #[inline]
+    pub fn static_resources(&self) -> Option<&StaticResources> {
+        match &self.manager {
+            Manager::Embed(manager) => Some(manager.resources()),
+            Manager::HotReload(_) => None,
+        }
+    }
+
+    /// Get the resources if they are read from their files.
+    #[inline]
+    pub fn file_resources(&self) -> Option<&FileResources> {
+        match &self.manager {
+            Manager::Embed(_) => None,
+            Manager::HotReload(manager) => Some(manager.resources()),
+        }
+    }
+
+    /// Check whether a resource is registered with the name.
+    #[inline]
+    pub fn contains<K: ResourceKey>(&self, key: K) -> bool {
+        match &self.manager {
+            Manager::Embed(manager) => manager.contains(key),
+            Manager::HotReload(manager) => manager.contains(key),
+        }
+    }
+
+    /// Get the URL which contains the hash of the current content of a resource. `None` if fingerprinted URLs are not enabled in the fairing, or the resource cannot be loaded.
+    #[inline]
+    pub fn fingerprinted_url<K: ResourceKey>(&self, key: K) -> Option<String> {
+        match &self.manager {
+            Manager::Embed(manager) => manager.fingerprinted_url(key),
+            Manager::HotReload(manager) => manager.fingerprinted_url(key),
+        }
+    }
+
+    /// Get the names of the resources along with the fingerprinted URLs of their current contents, e.g. to write a manifest. The resources which cannot be loaded are skipped. Nothing if fingerprinted URLs are not enabled in the fairing.
+    #[inline]
+    pub fn fingerprinted_urls(&self) -> impl Iterator<Item = (&'static str, String)> + '_ {
+        let urls: Box<dyn Iterator<Item = (&'static str, String)>> = match &self.manager {
+            Manager::Embed(manager) => Box::new(manager.fingerprinted_urls()),
+            Manager::HotReload(manager) => Box::new(manager.fingerprinted_urls()),
+        };
+
+        urls
+    }
+
+    #[inline]
+    pub(crate) fn fingerprinted_name<K: ResourceKey>(&self, key: K) -> Option<String> {
+        match &self.manager {
+            Manager::Embed(manager) => manager.fingerprinted_name(key),
+            Manager::HotReload(manager) => manager.fingerprinted_name(key),
+        }
+    }
+
+    /// Get the Subresource Integrity metadata of the current content of a resource, e.g. `sha384-...`, which can be the value of an `integrity` attribute. `None` if the resource does not exist or cannot be loaded.
+    #[inline]
+    pub fn integrity<K: ResourceKey>(&self, key: K) -> Option<String> {
+        match &self.manager {
+            Manager::Embed(manager) => manager.integrity(key),
+            Manager::HotReload(manager) => manager.integrity(key),
+        }
+    }
+
+    /// Get a view of a resource, after reloading it if needed in the hot-reload mode.
+    #[inline]
+    pub fn get_resource<K: ResourceKey>(&self, key: K) -> Result<Resource, ResourceError> {
+        match &self.manager {
+            Manager::Embed(manager) => manager.get_resource(key),
+            Manager::HotReload(manager) => manager.get_resource(key),
+        }
+    }
+
+    /// Build a `StaticResponse`. The preconditions can be a `&Preconditions` or an `&EtagIfNoneMatch`, and they are evaluated when responding. If the resource cannot be built, the response is `404 Not Found` for an unknown name, or `500 Internal Server Error` for a resource which cannot be loaded, and the error is logged.
+    #[inline]
+    pub fn build<P: Into<Preconditions>, K: ResourceKey>(
+        &self,
+        preconditions: P,
+        key: K,
+    ) -> StaticResponse {
+        match &self.manager {
+            Manager::Embed(manager) => manager.build(preconditions, key).into(),
+            Manager::HotReload(manager) => manager.build(preconditions, key).into(),
+        }
+    }
+
+    /// Attempt to build a `StaticResponse`.
+    #[inline]
+    pub fn try_build<P: Into<Preconditions>, K: ResourceKey>(
+        &self,
+        preconditions: P,
+        key: K,
+    ) -> Result<StaticResponse, ResourceError> {
+        match &self.manager {
+            Manager::Embed(manager) => {
+                manager.try_build(preconditions, key).map(StaticResponse::from)
+            },
+            Manager::HotReload(manager) => {
+                manager.try_build(preconditions, key).map(StaticResponse::from)
+            },
+        }
+    }
+}
@@ -0,0 +1,13 @@
+mod selected_resources;
+mod static_response;
+
+mod manager;
+
+mod fairing;
+
+mod macros;
+
+pub use fairing::*;
+pub use manager::*;
+pub use selected_resources::*;
+pub use static_response::*;
@@ -0,0 +1,280 @@
+use std::{fs, io, path::PathBuf, sync::Arc, time::SystemTime};
+
+use crate::{
+    debug::FileResources,
+    mime::Mime,
+    release::{StaticEntry, StaticResources},
+    substitution::Substitutions,
+    ContentEncoding, ResourceMetadata, ResourceMode,
+};
+
+/// The resources of the mode selected when Rocket ignites.
+#[derive(Debug)]
+pub enum SelectedResources {
+    /// The resources included into the executable binary file.
+    Embedded(StaticResources),
+    /// The resources read from their files.
+    Files(FileResources),
+}
+
+impl SelectedResources {
+    #[inline]
+    pub(crate) fn new(mode: ResourceMode) -> SelectedResources {
+        match mode {
+            ResourceMode::Embed => SelectedResources::Embedded(StaticResources::new()),
+            ResourceMode::HotReload => SelectedResources::Files(FileResources::new()),
+        }
+    }
+
+    /// Get the mode of the resources.
+    #[inline]
+    pub fn mode(&self) -> ResourceMode {
+        match self {
+            SelectedResources::Embedded(_) => ResourceMode::Embed,
+            SelectedResources::Files(_) => ResourceMode::HotReload,
+        }
+    }
+
+    /// Set the last modification date of the embedded resources. It does nothing in the hot-reload mode, where the modification times of the files are used.
+    #[inline]
+    pub fn set_last_modified(&mut self, last_modified: SystemTime) {
+        if let SelectedResources::Embedded(resources) = self {
+            resources.set_last_modified(last_modified);
+        }
+    }
+
+    /// Set the last modification date of the embedded resources along with its HTTP-date, like `StaticResources::set_last_modified_precomputed`. It does nothing in the hot-reload mode.
+    #[inline]
+    pub fn set_last_modified_precomputed(
+        &mut self,
+        last_modified: SystemTime,
+        http_date: &'static str,
+    ) {
+        if let SelectedResources::Embedded(resources) = self {
+            resources.set_last_modified_precomputed(last_modified, http_date);
+        }
+    }
+
+    /// Register a resource which is embedded or read from its file, depending on the mode. `mime`, `data`, `etag`, `digests` and `encoded` are only used in the embed mode, and `metadata` is used in both modes, where the entity tags and the digests are expected to be computed beforehand, like `StaticResources::register_resource_static_precomputed`. In the hot-reload mode, the MIME type is guessed from the extension of the file.
+    #[allow(clippy::too_many_arguments)]
+    #[inline]
+    pub fn register_resource<P: Into<PathBuf>>(
+        &mut self,
+        name: &'static str,
+        file_path: P,
+        mime: &'static str,
+        data: &'static [u8],
+        etag: &'static str,
+        digests: (&'static str, &'static str),
+        encoded: &'static [(ContentEncoding, &'static [u8], &'static str, &'static str)],
+        metadata: ResourceMetadata,
+    ) -> Result<(), io::Error> {
+        match self {
+            SelectedResources::Embedded(resources) => {
+                resources.register_resource_static_precomputed(
+                    name, mime, data, etag, digests, encoded, metadata,
+                );
+
+                Ok(())
+            },
+            SelectedResources::Files(resources) => {
+                resources.register_resource_file_with_metadata(name, file_path, metadata)
+            },
+        }
+    }
+
+    /// Register a resource whose data is owned, e.g. a `Vec<u8>`, a `String`, an `Arc<[u8]>` or a `Bytes`, in either mode.
+    #[inline]
+    pub fn register_resource_owned<D: AsRef<[u8]> + Send + Sync + 'static>(
+        &mut self,
+        name: &'static str,
+        mime: Mime,
+        data: D,
+    ) {
+        match self {
+            SelectedResources::Embedded(resources) => {
+                resources.register_resource_owned(name, mime, data)
+            },
+            SelectedResources::Files(resources) => {
+                resources.register_resource_owned(name, mime, data)
+            },
+        }
+    }
+
+    /// Register a resource whose data is produced by `f`, which is called once, in either mode.
+    #[inline]
+    pub fn register_resource_generated<D: AsRef<[u8]> + Send + Sync + 'static, F: FnOnce() -> D>(
+        &mut self,
+        name: &'static str,
+        mime: Mime,
+        f: F,
+    ) {
+        self.register_resource_owned(name, mime, f());
+    }
+
+    /// Register a resource whose data is static, like `StaticResources::register_resource_static`. In the hot-reload mode, the data is copied and never reloaded.
+    #[inline]
+    pub fn register_resource_static(
+        &mut self,
+        name: &'static str,
+        mime: Mime,
+        data: &'static [u8],
+    ) {
+        self.register_resource_static_encoded(name, mime, data, &[]);
+    }
+
+    /// Register a resource whose data is static along with its precompressed representations, like `StaticResources::register_resource_static_encoded`. In the hot-reload mode, only the identity representation is registered, like `register_resource_static`.
+    #[inline]
+    pub fn register_resource_static_encoded(
+        &mut self,
+        name: &'static str,
+        mime: Mime,
+        data: &'static [u8],
+        encoded: &[(ContentEncoding, &'static [u8])],
+    ) {
+        match self {
+            SelectedResources::Embedded(resources) => {
+                resources.register_resource_static_encoded(name, mime, data, encoded)
+            },
+            SelectedResources::Files(resources) => {
+                resources.register_resource_owned(name, mime, data)
+            },
+        }
+    }
+
+    /// Register a resource from a path, like `FileResources::register_resource_file`. In the embed mode, the file is read once, when it is registered, and the MIME type is guessed from its extension.
+    #[inline]
+    pub fn register_resource_file<P: Into<PathBuf>>(
+        &mut self,
+        name: &'static str,
+        file_path: P,
+    ) -> Result<(), io::Error> {
+        match self {
+            SelectedResources::Embedded(resources) => {
+                let path = file_path.into();
+
+                let data = fs::read(&path)?;
+
+                resources.register_resource_owned(
+                    name,
+                    mime_guess::from_path(&path).first_or_octet_stream(),
+                    data,
+                );
+
+                Ok(())
+            },
+            SelectedResources::Files(resources) => {
+                resources.register_resource_file(name, file_path)
+            },
+        }
+    }
+
+    #[inline]
+    pub(crate) fn substitute(
+        &mut self,
+        name: &str,
+        substitutions: &Arc<Substitutions>,
+    ) -> Result<(), String> {
+        match self {
+            SelectedResources::Embedded(resources) => resources.substitute(name, substitutions),
+            SelectedResources::Files(resources) => resources.substitute(name, substitutions),
+        }
+    }
+
+    /// Register a table of included resources in the embed mode, or the files of the resources in the hot-reload mode.
+    #[inline]
+    pub fn register_table(
+        &mut self,
+        table: &'static [StaticEntry],
+        files: &[(&'static str, &'static str, ResourceMetadata)],
+    ) -> Result<(), io::Error> {
+        match self {
+            SelectedResources::Embedded(resources) => {
+                resources.register_table(table);
+
+                Ok(())
+            },
+            SelectedResources::Files(resources) => {
+                for &(name, file_path, metadata) in files {
+                    resources.register_resource_file_with_metadata(name, file_path, metadata)?;
+                }
+
+                Ok(())
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::process;
+
+    use super::*;
+    use crate::{
+        rocket::{
+            http::{Header, Status},
+            local::blocking::Client,
+        },
+        EmbeddedFileServer, StaticResponse,
+    };
+
+    #[test]
+    fn register_resources_in_either_mode() {
+        let path =
+            std::env::temp_dir().join(format!("selected-resources-test-{}.css", process::id()));
+
+        fs::write(&path, "file").unwrap();
+
+        for mode in [ResourceMode::Embed, ResourceMode::HotReload] {
+            let file = path.clone();
+
+            let rocket = rocket::build()
+                .attach(
+                    StaticResponse::fairing(move |resources| {
+                        resources.register_resource_static(
+                            "static.txt",
+                            mime::TEXT_PLAIN,
+                            b"static",
+                        );
+                        resources.register_resource_static_encoded(
+                            "encoded.txt",
+                            mime::TEXT_PLAIN,
+                            b"identity",
+                            &[(ContentEncoding::Gzip, b"gzip")],
+                        );
+                        resources.register_resource_file("file.css", file.clone()).unwrap();
+                    })
+                    .mode(mode),
+                )
+                .mount("/", EmbeddedFileServer::new());
+
+            let client = Client::untracked(rocket).unwrap();
+
+            let response = client.get("/static.txt").dispatch();
+
+            assert_eq!(Some("static"), response.into_string().as_deref(), "{:?}", mode);
+
+            let response = client.get("/file.css").dispatch();
+
+            assert_eq!(Some("text/css"), response.headers().get_one("Content-Type"), "{:?}", mode);
+            assert_eq!(Some("file"), response.into_string().as_deref(), "{:?}", mode);
+
+            let response = client
+                .get("/encoded.txt")
+                .header(Header::new("Accept-Encoding", "gzip"))
+                .dispatch();
+
+            assert_eq!(Status::Ok, response.status(), "{:?}", mode);
+
+            // only the identity representation is registered in the hot-reload mode
+            let expected = match mode {
+                ResourceMode::Embed => "gzip",
+                ResourceMode::HotReload => "identity",
+            };
+
+            assert_eq!(Some(expected), response.into_string().as_deref(), "{:?}", mode);
+        }
+
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,47 @@
+use crate::{
+    debug, release,
+    rocket::{
+        request::Request,
+        response::{self, Responder},
+    },
+};
+
+#[derive(Debug)]
+enum Response {
+    Embed(release::StaticResponse),
+    HotReload(debug::StaticResponse),
+}
+
+#[derive(Debug)]
+/// To respond a static resource.
+pub struct StaticResponse {
+    response: Response,
+}
+
+impl From<release::StaticResponse> for StaticResponse {
+    #[inline]
+    fn from(response: release::StaticResponse) -> Self {
+        StaticResponse {
+            response: Response::Embed(response)
+        }
+    }
+}
+
+impl From<debug::StaticResponse> for StaticResponse {
+    #[inline]
+    fn from(response: debug::StaticResponse) -> Self {
+        StaticResponse {
+            response: Response::HotReload(response)
+        }
+    }
+}
+
+impl<'r, 'o: 'r> Responder<'r, 'o> for StaticResponse {
+    #[inline]
+    fn respond_to(self, request: &'r Request<'_>) -> response::Result<'o> {
+        match self.response {
+            Response::Embed(response) => response.respond_to(request),
+            Response::HotReload(response) => response.respond_to(request),
+        }
+    }
+}
@@ -0,0 +1,190 @@
+use std::{
+    error::Error,
+    fmt::{self, Display, Formatter},
+    io,
+};
+
+use crate::rocket::{
+    http::Status,
+    request::Request,
+    response::{self, Responder},
+};
+
+/// Errors which occur when getting a resource.
+#[derive(Debug)]
+pub enum ResourceError {
+    /// No resource is registered with the name.
+    NotFound { name: String },
+    /// The resource cannot be loaded, e.g. its file has been deleted.
+    Io { name: String, source: io::Error },
+}
+
+impl ResourceError {
+    #[inline]
+    pub(crate) fn not_found<S: Into<String>>(name: S) -> ResourceError {
+        ResourceError::NotFound {
+            name: name.into()
+        }
+    }
+
+    /// Get the name of the resource.
+    #[inline]
+    pub fn name(&self) -> &str {
+        match self {
+            ResourceError::NotFound {
+                name,
+            } => name,
+            ResourceError::Io {
+                name, ..
+            } => name,
+        }
+    }
+
+    /// Get the status of the response to send for this error, `404 Not Found` or `500 Internal Server Error`.
+    #[inline]
+    pub fn status(&self) -> Status {
+        match self {
+            ResourceError::NotFound {
+                ..
+            } => Status::NotFound,
+            ResourceError::Io {
+                ..
+            } => Status::InternalServerError,
+        }
+    }
+}
+
+impl Display for ResourceError {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        match self {
+            ResourceError::NotFound {
+                name,
+            } => write!(f, "The name `{}` is not found.", name),
+            ResourceError::Io {
+                name,
+                source,
+            } => write!(f, "The resource `{}` cannot be loaded: {}", name, source),
+        }
+    }
+}
+
+impl Error for ResourceError {
+    #[inline]
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ResourceError::NotFound {
+                ..
+            } => None,
+            ResourceError::Io {
+                source, ..
+            } => Some(source),
+        }
+    }
+}
+
+impl From<ResourceError> for io::Error {
+    #[inline]
+    fn from(error: ResourceError) -> Self {
+        match error {
+            ResourceError::NotFound {
+                ..
+            } => io::Error::new(io::ErrorKind::NotFound, error.to_string()),
+            ResourceError::Io {
+                source, ..
+            } => source,
+        }
+    }
+}
+
+/// Log the error and respond with its status, so that the catcher of the status handles it.
+impl<'r> Responder<'r, 'static> for ResourceError {
+    #[inline]
+    fn respond_to(self, _request: &'r Request<'_>) -> response::Result<'static> {
+        rocket::error_!("{}", self);
+
+        Err(self.status())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        rocket::{get, local::blocking::Client, routes, State},
+        Preconditions, ResourceMode, StaticContextManager, StaticResponse,
+    };
+
+    #[get("/<name>")]
+    fn resource(
+        manager: &State<StaticContextManager>,
+        name: &str,
+    ) -> Result<StaticResponse, ResourceError> {
+        manager.try_build(Preconditions::default(), name)
+    }
+
+    #[test]
+    fn map_errors_to_statuses() {
+        let not_found = ResourceError::not_found("a.txt");
+
+        assert_eq!(Status::NotFound, not_found.status());
+        assert_eq!(io::ErrorKind::NotFound, io::Error::from(not_found).kind());
+
+        let unloadable = ResourceError::Io {
+            name:   String::from("a.txt"),
+            source: io::Error::new(io::ErrorKind::PermissionDenied, "denied"),
+        };
+
+        assert_eq!(Status::InternalServerError, unloadable.status());
+        assert_eq!(io::ErrorKind::PermissionDenied, io::Error::from(unloadable).kind());
+    }
+
+    #[test]
+    fn respond_not_found() {
+        for &mode in ResourceMode::AVAILABLE {
+            let rocket = rocket::build()
+                .attach(
+                    StaticResponse::fairing(|resources| {
+                        resources.register_resource_owned("a.txt", mime::TEXT_PLAIN, "a");
+                    })
+                    .mode(mode),
+                )
+                .mount("/", routes![resource]);
+
+            let client = Client::untracked(rocket).unwrap();
+
+            assert_eq!(Status::Ok, client.get("/a.txt").dispatch().status(), "{:?}", mode);
+            assert_eq!(Status::NotFound, client.get("/b.txt").dispatch().status(), "{:?}", mode);
+        }
+    }
+
+    #[cfg(all(hot_reload, not(embed)))]
+    #[test]
+    fn respond_internal_server_error() {
+        let path = std::env::temp_dir().join(format!("error-test-{}.txt", std::process::id()));
+
+        std::fs::write(&path, "a").unwrap();
+
+        let file = path.clone();
+
+        let rocket = rocket::build()
+            .attach(StaticResponse::fairing(move |resources| {
+                resources.register_resource_file("a.txt", file.clone()).unwrap();
+            }))
+            .mount("/", routes![resource]);
+
+        let client = Client::untracked(rocket).unwrap();
+
+        assert_eq!(Status::Ok, client.get("/a.txt").dispatch().status());
+
+        std::fs::remove_file(&path).unwrap();
+
+        let manager = client.rocket().state::<StaticContextManager>().unwrap();
+
+        assert!(matches!(
+            manager.try_build(Preconditions::default(), "a.txt"),
+            Err(ResourceError::Io { .. })
+        ));
+        assert_eq!(Status::InternalServerError, client.get("/a.txt").dispatch().status());
+    }
+}
@@ -0,0 +1,166 @@
+use std::sync::Arc;
+
+use crate::{
+    fingerprint,
+    rocket::{http::uri::Origin, Build, Rocket},
+    substitution::Substitutions,
+    ResourceMode,
+};
+
+/// The options which are set with the builder methods of the fairing, the same in every mode.
+#[derive(Debug, Default)]
+pub(crate) struct FairingOptions {
+    pub(crate) fingerprint_base: Option<String>,
+    pub(crate) live_reload_url:  Option<String>,
+    pub(crate) mode:             Option<ResourceMode>,
+    pub(crate) substituted:      Vec<String>,
+    pub(crate) rewrite_base:     Option<String>,
+}
+
+impl FairingOptions {
+    /// Check the options which do not depend on the resources, so that Rocket fails to ignite instead of panicking later.
+    pub(crate) fn check(&self) -> Result<(), String> {
+        if self.rewrite_base.is_some() && self.fingerprint_base.is_none() {
+            return Err(String::from(
+                "Rewriting the references of static resources requires fingerprinted URLs.",
+            ));
+        }
+
+        if let Some(base) = self.fingerprint_base.as_deref() {
+            check_base(base, "fingerprinted URLs")?;
+        }
+
+        Ok(())
+    }
+
+    /// Call `f` with every resource name passed to `substitute`, along with the values of the placeholders.
+    pub(crate) fn substitute<F: FnMut(&str, &Arc<Substitutions>) -> Result<(), String>>(
+        &self,
+        rocket: &Rocket<Build>,
+        mut f: F,
+    ) -> Result<(), String> {
+        if self.substituted.is_empty() {
+            return Ok(());
+        }
+
+        let substitutions = Arc::new(Substitutions::from_rocket(rocket));
+
+        for name in self.substituted.iter() {
+            f(name, &substitutions)?;
+        }
+
+        Ok(())
+    }
+
+    /// Mount the routes of the fingerprinted URLs, and the live-reload endpoint in the hot-reload mode.
+    #[cfg_attr(not(hot_reload), allow(unused_variables))]
+    pub(crate) fn mount(&self, mut rocket: Rocket<Build>, mode: ResourceMode) -> Rocket<Build> {
+        if let Some(base) = self.fingerprint_base.as_deref() {
+            rocket = rocket.mount(base, fingerprint::routes());
+        }
+
+        #[cfg(hot_reload)]
+        if let (ResourceMode::HotReload, Some(url)) = (mode, self.live_reload_url.as_deref()) {
+            rocket = rocket.mount(url, crate::debug::live_reload::routes());
+        }
+
+        rocket
+    }
+}
+
+/// `Rocket::mount` panics with an invalid base, so check it before mounting.
+fn check_base(base: &str, usage: &str) -> Result<(), String> {
+    match Origin::parse(base) {
+        Ok(origin) if origin.query().is_none() => Ok(()),
+        Ok(_) => Err(format!("The base of {} {:?} cannot have a query.", usage, base)),
+        Err(err) => Err(format!(
+            "The base of {} {:?} is not a valid absolute path, e.g. \"/assets\": {}",
+            usage, base, err
+        )),
+    }
+}
+
+/// Implement the builder methods of `StaticResponseFairing`, which has an `options: FairingOptions` field, in the module where this is used.
+macro_rules! impl_fairing_options {
+    () => {
+        impl StaticResponseFairing {
+            /// Also serve every resource from a URL under `base` which contains the hash of its content, with `Cache-Control: public, max-age=31536000, immutable`. Use `StaticContextManager::fingerprinted_url` to get the URLs.
+            #[inline]
+            pub fn fingerprinted<S: Into<String>>(mut self, base: S) -> Self {
+                self.options.fingerprint_base = Some(base.into());
+
+                self
+            }
+
+            /// Mount a Server-Sent Events endpoint at `url`, which sends a `reload` event with the name of a resource every time the resource is reloaded, and inject a script which listens to it into every `text/html` resource, so that browsers reload the page by themselves. This only works in the hot-reload mode, and does nothing in the embed mode.
+            #[inline]
+            pub fn live_reload<S: Into<String>>(mut self, url: S) -> Self {
+                self.options.live_reload_url = Some(url.into());
+
+                self
+            }
+
+            /// Replace the `{{NAME}}` placeholders in the resources with the given names when Rocket ignites, e.g. `.substitute(["index.html", "env.js"])`. The value of a placeholder is looked up in the `static_resources_substitutions` configuration (e.g. `ROCKET_STATIC_RESOURCES_SUBSTITUTIONS={API_BASE_URL="https://api.example.com"}`), and then in the environment variables. The entity tags are computed after the substitution. Rocket fails to ignite if a resource is not registered or a placeholder has no value.
+            #[inline]
+            pub fn substitute<I: IntoIterator<Item = S>, S: Into<String>>(
+                mut self,
+                names: I,
+            ) -> Self {
+                self.options.substituted.extend(names.into_iter().map(Into::into));
+
+                self
+            }
+
+            /// Rewrite the `src` and `href` attributes in HTML resources and the `url()` functions in CSS resources which refer to other resources, so that they use the fingerprinted URLs, which requires `fingerprinted`. `base` is the URL where the resources are served by their names, e.g. `/static` if `EmbeddedFileServer` is mounted there, and a reference matches a resource if it is the URL of the resource under `base`, or its name. The entity tags of the rewritten resources are computed again. It is done when Rocket ignites in the embed mode, and every time a resource or a resource which it refers to is reloaded in the hot-reload mode.
+            #[inline]
+            pub fn rewrite_references<S: Into<String>>(mut self, base: S) -> Self {
+                self.options.rewrite_base = Some(base.into());
+
+                self
+            }
+
+            /// Select the mode instead of using the `static_resources_mode` configuration or the profile-based default. Rocket fails to ignite if the selected mode is not compiled in (see the `embed` and `hot-reload` features).
+            #[inline]
+            pub fn mode(mut self, mode: $crate::ResourceMode) -> Self {
+                self.options.mode = Some(mode);
+
+                self
+            }
+        }
+    };
+}
+
+pub(crate) use impl_fairing_options;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fingerprinted(base: &str) -> FairingOptions {
+        FairingOptions {
+            fingerprint_base: Some(base.to_string()),
+            ..FairingOptions::default()
+        }
+    }
+
+    #[test]
+    fn check_fingerprint_base() {
+        assert!(fingerprinted("/assets").check().is_ok());
+        assert!(fingerprinted("/").check().is_ok());
+        assert!(fingerprinted("/static/assets/").check().is_ok());
+        assert!(fingerprinted("assets").check().is_err());
+        assert!(fingerprinted("").check().is_err());
+        assert!(fingerprinted("/assets?v=1").check().is_err());
+        assert!(fingerprinted("/as sets").check().is_err());
+    }
+
+    #[test]
+    fn rewrite_references_requires_fingerprint() {
+        let options = FairingOptions {
+            rewrite_base: Some(String::from("/static")),
+            ..FairingOptions::default()
+        };
+
+        assert!(options.check().is_err());
+    }
+}
@@ -0,0 +1,295 @@
+use crate::{
+    rocket::{
+        http::{
+            uri::{fmt::Path, Segments},
+            Method, Status,
+        },
+        route::{Handler, Outcome, Route},
+        Data, Request,
+    },
+    Preconditions, ResourceError, StaticContextManager,
+};
+
+/// A handler which serves the resources of `StaticContextManager` by their names, so that one mount point can replace a route for every resource.
+///
+/// ```rust,ignore
+/// rocket::build()
+///     .attach(static_resources_directory!("dist"))
+///     .mount("/static", EmbeddedFileServer::new())
+/// ```
+///
+/// A request to `/static/js/app.js` is answered with the resource named `js/app.js`. Paths with segments that start with a `.` (including `.` and `..`) never match. Requests for resources which cannot be loaded, e.g. files deleted in the hot-reload mode, get `500 Internal Server Error`. Requests which do not match any resource are forwarded with `404 Not Found`, so they end up with a 404 response unless another route handles them.
+///
+/// For single-page applications with client-side routing, a fallback resource can be set, which answers unmatched requests preferring `text/html`, so that a missing `.js` file still gets a 404.
+///
+/// ```rust,ignore
+/// rocket::build()
+///     .attach(static_resources_directory!("dist"))
+///     .mount("/", EmbeddedFileServer::new().fallback("index.html").exclude_prefix("/api"))
+/// ```
+#[derive(Debug, Clone)]
+pub struct EmbeddedFileServer {
+    rank:              isize,
+    fallback:          Option<String>,
+    // the segments of the prefixes
+    excluded_prefixes: Vec<Vec<String>>,
+}
+
+impl EmbeddedFileServer {
+    /// The default rank of the route, which is the same as Rocket's `FileServer`.
+    const DEFAULT_RANK: isize = 10;
+
+    /// Create an `EmbeddedFileServer` whose route has a rank of `10`.
+    #[inline]
+    pub const fn new() -> EmbeddedFileServer {
+        EmbeddedFileServer {
+            rank:              Self::DEFAULT_RANK,
+            fallback:          None,
+            excluded_prefixes: Vec::new(),
+        }
+    }
+
+    /// Set the rank of the route.
+    #[inline]
+    pub const fn rank(mut self, rank: isize) -> EmbeddedFileServer {
+        self.rank = rank;
+
+        self
+    }
+
+    /// Set the name of the resource which answers the requests that do not match any resource and whose `Accept` header prefers `text/html`.
+    #[inline]
+    pub fn fallback<S: Into<String>>(mut self, name: S) -> EmbeddedFileServer {
+        self.fallback = Some(name.into());
+
+        self
+    }
+
+    /// Never handle requests whose path (including the mount point) is `prefix` or under `prefix`. For example, `/api` excludes `/api` and `/api/users`, but not `/apis`. The path of a request is percent-decoded and its dot segments are resolved before it is compared, so `/%61pi/users`, `/api%2Fusers` and `/x/../api/users` are excluded as well.
+    #[inline]
+    pub fn exclude_prefix<S: Into<String>>(mut self, prefix: S) -> EmbeddedFileServer {
+        let segments = prefix
+            .into()
+            .split('/')
+            .filter(|segment| !segment.is_empty())
+            .map(String::from)
+            .collect();
+
+        self.excluded_prefixes.push(segments);
+
+        self
+    }
+
+    fn is_excluded(&self, request: &Request<'_>) -> bool {
+        if self.excluded_prefixes.is_empty() {
+            return false;
+        }
+
+        let path = normalized_segments(request.uri().path().segments());
+
+        self.excluded_prefixes.iter().any(|prefix| {
+            prefix.len() <= path.len() && prefix.iter().zip(path.iter()).all(|(a, b)| a == b)
+        })
+    }
+}
+
+impl Default for EmbeddedFileServer {
+    #[inline]
+    fn default() -> Self {
+        EmbeddedFileServer::new()
+    }
+}
+
+/// Split decoded segments which contain `/`, drop empty segments and `.`, and resolve `..`.
+fn normalized_segments<'a, I: Iterator<Item = &'a str>>(segments: I) -> Vec<&'a str> {
+    let mut normalized = Vec::new();
+
+    for segment in segments.flat_map(|segment| segment.split('/')) {
+        match segment {
+            "" | "." => (),
+            ".." => {
+                normalized.pop();
+            },
+            _ => normalized.push(segment),
+        }
+    }
+
+    normalized
+}
+
+/// Get the resource name of the path of a request. `None` if a segment starts with a `.`, including `.` and `..`.
+pub(crate) fn requested_name(request: &Request<'_>) -> Option<String> {
+    let segments = request.segments::<Segments<'_, Path>>(0..).ok()?;
+
+    let mut name = String::new();
+
+    for segment in segments {
+        if segment.starts_with('.') {
+            return None;
+        }
+
+        if !name.is_empty() {
+            name.push('/');
+        }
+
+        name.push_str(segment);
+    }
+
+    Some(name)
+}
+
+#[rocket::async_trait]
+impl Handler for EmbeddedFileServer {
+    async fn handle<'r>(&self, request: &'r Request<'_>, data: Data<'r>) -> Outcome<'r> {
+        let manager = match request.rocket().state::<StaticContextManager>() {
+            Some(manager) => manager,
+            None => return Outcome::forward(data, Status::NotFound),
+        };
+
+        if self.is_excluded(request) {
+            return Outcome::forward(data, Status::NotFound);
+        }
+
+        if let Some(name) = requested_name(request).filter(|name| !name.is_empty()) {
+            match manager.try_build(Preconditions::from_request_headers(request), name) {
+                Ok(responder) => return Outcome::from(request, responder),
+                Err(ResourceError::NotFound {
+                    ..
+                }) => (),
+                // a resource which cannot be loaded is not replaced by the fallback
+                Err(error) => return Outcome::from(request, error),
+            }
+        }
+
+        if let Some(fallback) = self.fallback.as_ref() {
+            let prefers_html = request
+                .accept()
+                .map(|accept| accept.preferred().media_type().is_html())
+                .unwrap_or(false);
+
+            if prefers_html {
+                match manager.try_build(Preconditions::from_request_headers(request), fallback) {
+                    Ok(responder) => return Outcome::from(request, responder),
+                    Err(ResourceError::NotFound {
+                        ..
+                    }) => (),
+                    Err(error) => return Outcome::from(request, error),
+                }
+            }
+        }
+
+        Outcome::forward(data, Status::NotFound)
+    }
+}
+
+impl From<EmbeddedFileServer> for Vec<Route> {
+    #[inline]
+    fn from(server: EmbeddedFileServer) -> Self {
+        let mut route = Route::ranked(server.rank, Method::Get, "/<path..>", server);
+
+        route.name = Some("EmbeddedFileServer".into());
+
+        vec![route]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rocket::{
+        http::{Accept, Status},
+        local::blocking::Client,
+    };
+
+    fn client() -> Client {
+        let rocket = rocket::build()
+            .attach(crate::StaticResponse::fairing(|resources| {
+                resources.register_resource_owned("index.html", mime::TEXT_HTML, "index");
+                resources.register_resource_owned("app.js", mime::APPLICATION_JAVASCRIPT, "app");
+                resources.register_resource_owned(".hidden", mime::TEXT_PLAIN, "hidden");
+                resources.register_resource_owned("x/../app.css", mime::TEXT_CSS, "dot segments");
+            }))
+            .mount("/", EmbeddedFileServer::new().fallback("index.html").exclude_prefix("/api/"));
+
+        Client::untracked(rocket).unwrap()
+    }
+
+    fn get(client: &Client, path: &str) -> (Status, Option<String>) {
+        let response = client.get(path.to_string()).header(Accept::HTML).dispatch();
+
+        (response.status(), response.into_string())
+    }
+
+    #[test]
+    fn normalize_segments() {
+        assert_eq!(vec!["api", "users"], normalized_segments(["api/users"].into_iter()));
+        assert_eq!(vec!["api"], normalized_segments(["x", "..", ".", "api", ""].into_iter()));
+        assert_eq!(vec!["api"], normalized_segments(["..", "..", "api"].into_iter()));
+    }
+
+    #[test]
+    fn serve_resources_and_fallback() {
+        let client = client();
+
+        assert_eq!((Status::Ok, Some(String::from("app"))), get(&client, "/app.js"));
+        assert_eq!((Status::Ok, Some(String::from("index"))), get(&client, "/users/1"));
+        assert_eq!((Status::Ok, Some(String::from("index"))), get(&client, "/apis"));
+    }
+
+    #[test]
+    fn never_serve_excluded_prefixes() {
+        let client = client();
+
+        for path in [
+            "/api",
+            "/api/",
+            "/api/users",
+            "/%61pi/users",
+            "/api%2Fusers",
+            "//api//users",
+            "/x/../api/users",
+        ] {
+            assert_eq!(Status::NotFound, get(&client, path).0, "{}", path);
+        }
+    }
+
+    #[test]
+    fn never_serve_dot_segments() {
+        let client = client();
+
+        for path in ["/.hidden", "/x/../app.css", "/x/./app.js", "/%2Ehidden"] {
+            let response = client.get(path).dispatch();
+
+            assert_eq!(Status::NotFound, response.status(), "{}", path);
+        }
+
+        assert_eq!((Status::Ok, Some(String::from("index"))), get(&client, "/.hidden"));
+    }
+
+    #[cfg(all(hot_reload, not(embed)))]
+    #[test]
+    fn respond_unloadable_resources_with_errors() {
+        let path =
+            std::env::temp_dir().join(format!("file-server-test-{}.txt", std::process::id()));
+
+        std::fs::write(&path, "file").unwrap();
+
+        let file = path.clone();
+
+        let rocket = rocket::build()
+            .attach(crate::StaticResponse::fairing(move |resources| {
+                resources.register_resource_owned("index.html", mime::TEXT_HTML, "index");
+                resources.register_resource_file("file.txt", file.clone()).unwrap();
+            }))
+            .mount("/", EmbeddedFileServer::new().fallback("index.html"));
+
+        let client = Client::untracked(rocket).unwrap();
+
+        assert_eq!((Status::Ok, Some(String::from("file"))), get(&client, "/file.txt"));
+
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(Status::InternalServerError, get(&client, "/file.txt").0);
+        assert_eq!((Status::Ok, Some(String::from("index"))), get(&client, "/other.txt"));
+    }
+}
@@ -0,0 +1,233 @@
+use crate::{
+    file_server,
+    rocket::{
+        http::{Method, Status},
+        response::Responder,
+        route::{Handler, Outcome, Route},
+        Data, Request,
+    },
+    EntityTag, Preconditions, StaticContextManager,
+};
+
+/// The `Cache-Control` value of the responses sent from fingerprinted URLs.
+pub(crate) const IMMUTABLE_CACHE_CONTROL: &str = "public, max-age=31536000, immutable";
+
+/// Insert the tag of an entity tag into a name, before its extension if any. `"js/app.js"` becomes something like `"js/app.3f9a1c_k-2Q.js"`.
+pub(crate) fn fingerprinted_name(name: &str, etag: &EntityTag<'_>) -> String {
+    // make the base64 tag URL-safe
+    let hash = etag.get_tag().replace('+', "-").replace('/', "_");
+
+    let file_name_start = name.rfind('/').map(|i| i + 1).unwrap_or(0);
+
+    match name[file_name_start..].rfind('.') {
+        Some(i) if i > 0 => {
+            let (stem, extension) = name.split_at(file_name_start + i);
+
+            format!("{}.{}{}", stem, hash, extension)
+        },
+        _ => format!("{}.{}", name, hash),
+    }
+}
+
+/// Join a mount point and a fingerprinted name.
+#[inline]
+pub(crate) fn join_url(base: &str, fingerprinted_name: &str) -> String {
+    format!("{}/{}", base.trim_end_matches('/'), fingerprinted_name)
+}
+
+/// Get the names which a fingerprinted name could come from.
+fn candidate_names(fingerprinted_name: &str) -> impl Iterator<Item = String> + '_ {
+    let file_name_start = fingerprinted_name.rfind('/').map(|i| i + 1).unwrap_or(0);
+    let file_name = &fingerprinted_name[file_name_start..];
+
+    // `stem.hash`
+    let without_extension = file_name
+        .rfind('.')
+        .filter(|&i| i > 0)
+        .map(|i| fingerprinted_name[..file_name_start + i].to_string());
+
+    // `stem.hash.extension`
+    let with_extension = file_name.rfind('.').and_then(|extension_start| {
+        file_name[..extension_start].rfind('.').filter(|&i| i > 0).map(|hash_start| {
+            format!(
+                "{}{}",
+                &fingerprinted_name[..file_name_start + hash_start],
+                &file_name[extension_start..]
+            )
+        })
+    });
+
+    with_extension.into_iter().chain(without_extension)
+}
+
+/// Serves the resources from their fingerprinted URLs.
+#[derive(Clone)]
+struct FingerprintHandler;
+
+#[rocket::async_trait]
+impl Handler for FingerprintHandler {
+    async fn handle<'r>(&self, request: &'r Request<'_>, data: Data<'r>) -> Outcome<'r> {
+        let manager = match request.rocket().state::<StaticContextManager>() {
+            Some(manager) => manager,
+            None => return Outcome::forward(data, Status::NotFound),
+        };
+
+        let path = match file_server::requested_name(request) {
+            Some(path) => path,
+            None => return Outcome::forward(data, Status::NotFound),
+        };
+
+        for name in candidate_names(&path) {
+            // an outdated hash is not found, otherwise the new content would be cached as the old one
+            if manager.fingerprinted_name(&name).as_deref() != Some(path.as_str()) {
+                continue;
+            }
+
+            let responder =
+                match manager.try_build(Preconditions::from_request_headers(request), &name) {
+                    Ok(responder) => responder,
+                    Err(_) => break,
+                };
+
+            return match responder.respond_to(request) {
+                Ok(mut response) => {
+                    response.set_raw_header("Cache-Control", IMMUTABLE_CACHE_CONTROL);
+
+                    Outcome::Success(response)
+                },
+                Err(status) => Outcome::Error(status),
+            };
+        }
+
+        Outcome::forward(data, Status::NotFound)
+    }
+}
+
+/// The route which should be mounted at the base of fingerprinted URLs.
+#[inline]
+pub(crate) fn routes() -> Vec<Route> {
+    vec![Route::new(Method::Get, "/<path..>", FingerprintHandler)]
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        rocket::{http::Status, local::blocking::Client},
+        EmbeddedFileServer, ResourceMode, StaticContextManager, StaticResponse,
+    };
+
+    const NAMES: [&str; 5] = ["index.html", "about.html", "a.css", "b.css", "logo.png"];
+
+    fn client(mode: ResourceMode) -> Client {
+        let rocket = rocket::build()
+            .attach(
+                StaticResponse::fairing(|resources| {
+                    // pages which link to each other and to themselves, and stylesheets which import each other
+                    resources.register_resource_owned(
+                        "index.html",
+                        mime::TEXT_HTML,
+                        r#"<link href="a.css"><a href="index.html">home</a><a href="about.html">about</a>"#,
+                    );
+                    resources.register_resource_owned(
+                        "about.html",
+                        mime::TEXT_HTML,
+                        r#"<link href="b.css"><a href="index.html">home</a>"#,
+                    );
+                    resources.register_resource_owned(
+                        "a.css",
+                        mime::TEXT_CSS,
+                        "@import url(b.css); body { background: url(logo.png) }",
+                    );
+                    resources.register_resource_owned(
+                        "b.css",
+                        mime::TEXT_CSS,
+                        "@import url(a.css); @import url(b.css);",
+                    );
+                    resources.register_resource_owned("logo.png", mime::IMAGE_PNG, "png");
+                })
+                .fingerprinted("/assets")
+                .rewrite_references("/static")
+                .mode(mode),
+            )
+            .mount("/static", EmbeddedFileServer::new());
+
+        Client::untracked(rocket).unwrap()
+    }
+
+    /// Every fingerprinted URL in the rewritten resources, and every fingerprinted URL of the manager, is served.
+    fn serve_every_fingerprinted_url(mode: ResourceMode) {
+        let client = client(mode);
+
+        let manager = client.rocket().state::<StaticContextManager>().unwrap();
+
+        let mut urls = Vec::new();
+
+        for name in NAMES {
+            let body = client.get(format!("/static/{}", name)).dispatch().into_string().unwrap();
+
+            for (start, _) in body.match_indices("/assets/") {
+                let len = body[start..].find(['"', ')']).unwrap();
+
+                urls.push(body[start..start + len].to_string());
+            }
+
+            urls.push(manager.fingerprinted_url(name).unwrap());
+        }
+
+        // the references which close a cycle are left as they are, so some of them are rewritten
+        assert!(urls.len() > NAMES.len());
+
+        for url in urls {
+            assert_eq!(Status::Ok, client.get(url.as_str()).dispatch().status(), "{}", url);
+        }
+    }
+
+    #[test]
+    fn list_fingerprinted_urls() {
+        for &mode in ResourceMode::AVAILABLE {
+            let client = client(mode);
+
+            let manager = client.rocket().state::<StaticContextManager>().unwrap();
+
+            let urls = manager.fingerprinted_urls().collect::<Vec<_>>();
+
+            assert_eq!(
+                NAMES.as_slice(),
+                urls.iter().map(|&(name, _)| name).collect::<Vec<_>>(),
+                "{:?}",
+                mode
+            );
+
+            for (name, url) in urls {
+                assert_eq!(manager.fingerprinted_url(name).as_ref(), Some(&url), "{:?}", mode);
+                assert_eq!(Status::Ok, client.get(url.as_str()).dispatch().status(), "{}", url);
+            }
+
+            // without fingerprinted URLs
+            let rocket = rocket::build().attach(
+                StaticResponse::fairing(|resources| {
+                    resources.register_resource_owned("logo.png", mime::IMAGE_PNG, "png");
+                })
+                .mode(mode),
+            );
+
+            let client = Client::untracked(rocket).unwrap();
+
+            let manager = client.rocket().state::<StaticContextManager>().unwrap();
+
+            assert_eq!(0, manager.fingerprinted_urls().count(), "{:?}", mode);
+        }
+    }
+
+    #[cfg(embed)]
+    #[test]
+    fn serve_every_fingerprinted_url_embedded() {
+        serve_every_fingerprinted_url(ResourceMode::Embed);
+    }
+
+    #[cfg(hot_reload)]
+    #[test]
+    fn serve_every_fingerprinted_url_hot_reloaded() {
+        serve_every_fingerprinted_url(ResourceMode::HotReload);
+    }
+}
@@ -1,6 +1,131 @@
+#[cfg(embed)]
+use std::borrow::Cow;
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use sha2::{Digest, Sha256, Sha384};
+
 use crate::EntityTag;
 
 #[inline]
 pub(crate) fn compute_data_etag<B: AsRef<[u8]> + ?Sized>(data: &B) -> EntityTag<'static> {
     EntityTag::from_data(data)
 }
+
+/// Compute the Subresource Integrity metadata of data, e.g. `sha384-...`.
+#[inline]
+pub(crate) fn compute_integrity<B: AsRef<[u8]> + ?Sized>(data: &B) -> String {
+    format!("sha384-{}", STANDARD.encode(Sha384::digest(data.as_ref())))
+}
+
+/// Compute the value of the `Content-Digest` header (RFC 9530) of data, e.g. `sha-256=:...:`. SHA-384 is not one of the registered algorithms, so SHA-256 is used.
+#[inline]
+pub(crate) fn compute_content_digest<B: AsRef<[u8]> + ?Sized>(data: &B) -> String {
+    format!("sha-256=:{}:", STANDARD.encode(Sha256::digest(data.as_ref())))
+}
+
+/// Use an entity tag computed beforehand, usually at compile time, along with the value of the `ETag` header. If it is not valid, compute it from the data instead.
+#[cfg(embed)]
+pub(crate) fn precomputed_etag(
+    data: &[u8],
+    etag: &'static str,
+) -> (EntityTag<'static>, Cow<'static, str>) {
+    match EntityTag::with_str(false, etag) {
+        // a quoted tag is already the value of the header
+        Ok(tag) if etag.starts_with('"') => (tag, Cow::Borrowed(etag)),
+        Ok(tag) => {
+            let header = tag.to_string();
+
+            (tag, Cow::Owned(header))
+        },
+        Err(_) => {
+            let tag = compute_data_etag(data);
+            let header = tag.to_string();
+
+            (tag, Cow::Owned(header))
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        rocket::{
+            http::{Header, Status},
+            local::blocking::Client,
+        },
+        EmbeddedFileServer, ResourceMode, StaticResponse,
+    };
+
+    const README: &[u8] = include_bytes!("../examples/front-end/html/README.html");
+
+    #[test]
+    fn compute_etag_at_compile_time() {
+        let etag = crate::rocket_include_static_resources_macros::include_etag!(
+            "examples/front-end/html/README.html"
+        );
+
+        assert_eq!(compute_data_etag(README).to_string(), etag);
+    }
+
+    #[test]
+    fn compute_digests_at_compile_time() {
+        let (integrity, content_digest) = crate::rocket_include_static_resources_macros::include_digests!(
+            "examples/front-end/html/README.html"
+        );
+
+        assert_eq!(compute_integrity(README), integrity);
+        assert_eq!(compute_content_digest(README), content_digest);
+    }
+
+    #[cfg(feature = "compression")]
+    #[test]
+    fn compute_digests_of_encoded_data_at_compile_time() {
+        let encoded: &[(crate::ContentEncoding, &[u8], &str, &str)] = crate::rocket_include_static_resources_macros::include_encoded!(
+            crate,
+            "examples/front-end/html/README.html"
+        );
+
+        assert!(encoded.iter().any(|&(encoding, ..)| encoding == crate::ContentEncoding::Gzip));
+
+        for &(encoding, data, _, content_digest) in encoded {
+            assert_eq!(compute_content_digest(data), content_digest, "{:?}", encoding);
+        }
+    }
+
+    fn client(mode: ResourceMode) -> Client {
+        let rocket = rocket::build()
+            .attach(
+                StaticResponse::fairing(|resources| {
+                    resources.register_resource_owned("a.txt", mime::TEXT_PLAIN, "0123456789");
+                })
+                .mode(mode),
+            )
+            .mount("/", EmbeddedFileServer::new());
+
+        Client::untracked(rocket).unwrap()
+    }
+
+    #[test]
+    fn send_content_digest_of_full_responses_only() {
+        for &mode in ResourceMode::AVAILABLE {
+            let client = client(mode);
+
+            let response = client.get("/a.txt").dispatch();
+
+            assert_eq!(Status::Ok, response.status(), "{:?}", mode);
+            assert_eq!(
+                Some(compute_content_digest(b"0123456789").as_str()),
+                response.headers().get_one("Content-Digest"),
+                "{:?}",
+                mode
+            );
+
+            let response =
+                client.get("/a.txt").header(Header::new("Range", "bytes=0-1")).dispatch();
+
+            assert_eq!(Status::PartialContent, response.status(), "{:?}", mode);
+            assert_eq!(None, response.headers().get_one("Content-Digest"), "{:?}", mode);
+        }
+    }
+}
@@ -0,0 +1,126 @@
+/// A key to look up a static resource. It is a name, such as a `&str` or a `String`, or a typed handle generated by `static_resources!`.
+pub trait ResourceKey {
+    /// Get the name of the resource.
+    fn name(&self) -> &str;
+
+    /// Get the index of the resource in the order of registration, if it is known. It is only a hint, the name is still compared, so a resource registered in another order is looked up by its name instead.
+    #[inline]
+    fn index(&self) -> Option<usize> {
+        None
+    }
+}
+
+impl<S: AsRef<str> + ?Sized> ResourceKey for S {
+    #[inline]
+    fn name(&self) -> &str {
+        self.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::table::ResourceTable;
+
+    /// A name with an index hint.
+    struct Hinted(&'static str, usize);
+
+    impl ResourceKey for Hinted {
+        #[inline]
+        fn name(&self) -> &str {
+            self.0
+        }
+
+        #[inline]
+        fn index(&self) -> Option<usize> {
+            Some(self.1)
+        }
+    }
+
+    fn table() -> ResourceTable<u8> {
+        let mut table = ResourceTable::new();
+
+        table.insert("a", 0);
+        table.insert("b", 1);
+        table.insert("c", 2);
+
+        table
+    }
+
+    #[test]
+    fn look_up_by_index() {
+        let table = table();
+
+        assert_eq!(Some(("b", &1)), table.get(&Hinted("b", 1)));
+        assert_eq!(Some(("b", &1)), table.get("b"));
+    }
+
+    #[test]
+    fn fall_back_to_name() {
+        let table = table();
+
+        // the hint points to another resource
+        assert_eq!(Some(("b", &1)), table.get(&Hinted("b", 2)));
+        // the hint is out of range
+        assert_eq!(Some(("b", &1)), table.get(&Hinted("b", 9)));
+        // the name is never ignored
+        assert_eq!(None, table.get(&Hinted("d", 1)));
+    }
+
+    #[test]
+    fn look_up_typed_handles() {
+        use crate::{
+            rocket::local::blocking::Client, ResourceMode, StaticContextManager, StaticResponse,
+        };
+
+        crate::static_resources! {
+            enum Resources {
+                Readme = "readme" => "examples/front-end/html/README.html",
+                Favicon = "favicon" => "examples/front-end/images/favicon.ico",
+            }
+        }
+
+        for &mode in ResourceMode::AVAILABLE {
+            // registered in the same order as the handles, or in another order
+            let fairings = [
+                Resources::fairing().mode(mode),
+                StaticResponse::fairing(|resources| {
+                    resources.register_resource_owned("favicon", mime::IMAGE_PNG, "favicon");
+                    resources.register_resource_owned("readme", mime::TEXT_HTML, "readme");
+                })
+                .mode(mode),
+            ];
+
+            for fairing in fairings {
+                let client = Client::untracked(rocket::build().attach(fairing)).unwrap();
+
+                let manager = client.rocket().state::<StaticContextManager>().unwrap();
+
+                for &resource in Resources::ALL {
+                    assert_eq!(
+                        manager.get_resource(resource.name()).unwrap().bytes(),
+                        manager.get_resource(resource).unwrap().bytes(),
+                        "{:?}",
+                        mode
+                    );
+                }
+            }
+        }
+    }
+
+    #[cfg(hot_reload)]
+    #[test]
+    fn fall_back_to_name_after_removal() {
+        let mut table = table();
+
+        table.remove("a");
+        table.insert("a", 3);
+
+        // the index of the removed resource is not reused
+        assert_eq!(Some(("a", &3)), table.get(&Hinted("a", 0)));
+
+        table.remove("b");
+
+        assert_eq!(None, table.get(&Hinted("b", 1)));
+    }
+}
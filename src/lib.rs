@@ -14,7 +14,7 @@ extern crate rocket_include_static_resources;
 
 use rocket::State;
 
-use rocket_include_static_resources::{EtagIfNoneMatch, StaticContextManager, StaticResponse};
+use rocket_include_static_resources::{Preconditions, StaticContextManager, StaticResponse};
 
 static_response_handler! {
     "/favicon.ico" => favicon => "favicon",
@@ -24,9 +24,9 @@ static_response_handler! {
 #[get("/")]
 fn index(
     static_resources: &State<StaticContextManager>,
-    etag_if_none_match: EtagIfNoneMatch,
+    preconditions: Preconditions,
 ) -> StaticResponse {
-    static_resources.build(&etag_if_none_match, "html-readme")
+    static_resources.build(preconditions, "html-readme")
 }
 
 #[launch]
@@ -42,8 +42,28 @@ fn rocket() -> _ {
 }
 ```
 
-* `static_resources_initializer!` is used for including files into your executable binary file. You need to specify each file's name and its path relative to the directory containing the manifest of your package. For instance, the above example uses **favicon** to represent the file **included-static-resources/favicon.ico** and **favicon_png** to represent the file **included-static-resources/favicon.png**. A name cannot be repeating. In order to reduce the compilation time and allow to hot-reload resources, files are compiled into your executable binary file together, only when you are using the **release** profile.
-* `static_response_handler!` is used for quickly creating **GET** route handlers to retrieve static resources.
+* `static_resources_initializer!` is used for including files into your executable binary file. You need to specify each file's name and its path relative to the directory containing the manifest of your package. For instance, the above example uses **favicon** to represent the file **included-static-resources/favicon.ico** and **favicon_png** to represent the file **included-static-resources/favicon.png**. A name cannot be repeating, and a repeated name is a compile error, including a name which is also found by `static_resources_initialize_directory!` in the same fairing. In order to reduce the compilation time and allow to hot-reload resources, files are compiled into your executable binary file together, only when you are using the **release** profile. Their entity tags are computed at compile time as well, so the files are not hashed when Rocket ignites. The header values (`Content-Type`, `ETag` and `Last-Modified`) are rendered once as well, so serving an embedded resource allocates nothing besides what Rocket needs.
+* A resource in `static_resources_initializer!` (and the other initializer macros) can be followed by a semicolon and metadata, e.g. `"feed" => "data/feed.xml"; { mime: "application/atom+xml", charset: "utf-8", headers: { "X-Robots-Tag": "noindex" } }`. `mime` overrides the MIME type guessed from the extension, `charset` is appended to the MIME type, and `headers` are added to every response of the resource. The metadata is checked at compile time.
+* `static_resource_names! { pub mod names { FAVICON = "favicon", FAVICON_PNG = "favicon-png" } }` declares the set of names as constants (plus `names::ALL`). Use `names::FAVICON` instead of `"favicon"` in `static_resources_initializer!`, `static_response_handler!` and `build`, so a misspelled name does not compile. Repeated names in the set are compile errors as well.
+* `static_resources! { pub enum Resources { Favicon = "favicon" => "examples/front-end/images/favicon.ico", ... } }` generates a typed handle for each resource, and `Resources::fairing()` registers them. `StaticContextManager::build` and `get_resource` accept a handle (e.g. `Resources::Favicon`) as well as a name, and look it up by its index instead of hashing the name, so renaming a resource is checked by the compiler.
+* `static_resources_initializer!(table; ...)` puts the included resources into a `static` table which is sorted by name at compile time, so no map is built and nothing is hashed when Rocket ignites, and a resource is looked up by a binary search. The names must be literal strings. `static_resources_initialize_table!` does the same inside a custom `StaticResponse::fairing`. In the hot-reload mode, it is the same as `static_resources_initializer!`.
+* <code>static_resources_directory!("dist", include = "\*\*&#47;\*.{js,css}", exclude = "\*\*&#47;\*.map")</code> is used like `static_resources_initializer!`, but includes every file in a directory, named by its path relative to the directory (e.g. `js/app.js`). The globs are matched against those paths, and `*` does not match `/`, so `*.js` only matches the files directly in the directory while <code>\*\*&#47;\*.js</code> matches them in every subdirectory as well. `static_resources_initialize_directory!` does the same inside a custom `StaticResponse::fairing`, so it can be combined with `static_resources_initialize!`.
+* `register_resource_owned` registers data which is generated at runtime (a `Vec<u8>`, a `String`, an `Arc<[u8]>`, a `Bytes`, or anything else which is `AsRef<[u8]>`), e.g. `resources.register_resource_owned("config.js", mime::APPLICATION_JAVASCRIPT, config)` inside `StaticResponse::fairing`, and `register_resource_generated` registers the data returned by a closure, which is called once when Rocket ignites. They are available on `StaticResources`, `FileResources` and `SelectedResources`, and the resources get entity tags and responses like the included files.
+* `static_response_handler!` is used for quickly creating **GET** route handlers to retrieve static resources. `StaticContextManager::build` never panics: an unknown name responds `404 Not Found` and a resource which cannot be loaded responds `500 Internal Server Error`, with a log line. Use `try_build` to handle a `ResourceError` by yourself.
+* `StaticResponse` is a Rocket `Sentinel`, so the launch is aborted if the fairing is not attached. The handlers generated by `static_response_handler!` also abort the launch if a name which is a literal string, or a path to a constant or a typed handle, is not registered.
+* `EmbeddedFileServer` is a handler which serves every resource by its name, e.g. `.mount("/static", EmbeddedFileServer::new())` serves the resource named `js/app.js` at `/static/js/app.js`. Paths with segments that start with a `.` (e.g. `..` or `.hidden`) never match, unknown names end up with `404 Not Found`, and resources which cannot be loaded with `500 Internal Server Error`. Its rank is `10` by default and can be changed by `EmbeddedFileServer::rank`. For single-page applications, `EmbeddedFileServer::fallback("index.html")` answers unmatched requests which prefer `text/html` with that resource, and `EmbeddedFileServer::exclude_prefix("/api")` keeps paths under `/api` away from the handler.
+* `Preconditions` is a request guard which gathers the `If-Match`, `If-None-Match`, `If-Modified-Since` and `If-Unmodified-Since` headers. `StaticResponse` evaluates them as RFC 9110 describes and responds `304 Not Modified` or `412 Precondition Failed` if needed. The last modification date is the modification time of the file in the **debug** profile, and the build time in the **release** profile. An `&EtagIfNoneMatch` can still be passed to `build` instead.
+* `StaticContextManager::integrity` returns the Subresource Integrity metadata (`sha384-...`) of a resource, e.g. for `<script src="..." integrity="...">`. Every full response of `StaticResponse` also has a `Content-Digest` header (RFC 9530) of the content it sends, with SHA-256 because SHA-384 is not a registered algorithm for it. The digests of the included files (and of their precompressed representations) are computed at compile time in the embed mode, and when a file is loaded in the hot-reload mode.
+* `StaticResponse` advertises `Accept-Ranges: bytes` and answers `Range` requests (including multiple ranges and `If-Range`) with `206 Partial Content`, or `416 Range Not Satisfiable`.
+* `static_resources_initializer!(fingerprint = "/assets"; ...)` additionally serves every resource from a URL under `/assets` which contains the hash of its content (for example, `app.js` becomes `/assets/app.G8W9C3oz6cY.js`), with `Cache-Control: public, max-age=31536000, immutable`. `StaticContextManager::fingerprinted_url` returns the current URL of a resource, and `StaticContextManager::fingerprinted_urls` the URLs of every resource, e.g. to write a manifest or a list to preload.
+* `static_resources_initializer!(fingerprint = "/assets"; ...).rewrite_references("/static")` rewrites the `src` and `href` attributes of the tags in HTML resources (text, comments, scripts and styles are left alone) and the `url()` functions in CSS resources which refer to registered resources, so that they use the fingerprinted URLs. `/static` is where the resources are served by their names (e.g. with `EmbeddedFileServer`), and a reference matches a resource if it is a URL under `/static`, or a relative URL resolved against the referring resource. The entity tags are computed again, including the ones of the resources which refer to rewritten stylesheets. It is done when Rocket ignites in the embed mode, and whenever a resource or something it refers to is reloaded in the hot-reload mode. The files in the overlay directory are rewritten too, along with the embedded resources which refer to them, every time the directory is scanned.
+* With the `compression` feature enabled, gzip, brotli and zstd representations of every file are compressed at compile time in the embed mode, and the one to send is negotiated with the `Accept-Encoding` header of each request. Compressing at the highest levels takes a while in an unoptimized proc macro, so you may want to set `opt-level = 3` in `[profile.release.build-override]`.
+* `static_resources_initializer!(...).substitute(["index.html", "env.js"])` replaces the `{{NAME}}` placeholders in those resources when Rocket ignites, with the values in the `static_resources_substitutions` configuration (e.g. `ROCKET_STATIC_RESOURCES_SUBSTITUTIONS={API_BASE_URL="https://api.example.com"}`) or the environment variables, and computes their entity tags again. A placeholder without a value makes Rocket fail to ignite. In the hot-reload mode, the substitution is applied again every time a file is reloaded. The precompressed representations of a substituted resource are not used.
+* In the embed mode, the `static_resources_overlay` configuration (e.g. `ROCKET_STATIC_RESOURCES_OVERLAY=/etc/my-app/overrides`) sets an overlay directory. A file in it whose path relative to the directory is the name of a registered resource is served instead of the embedded resource, with its own `ETag`, `Last-Modified` and MIME type (guessed from its extension, or the one of the embedded resource). The file of a resource passed to `substitute` is substituted, and a file which cannot be substituted is an error which keeps the previous one. The directory is scanned again on `SIGHUP` (on Unix), and every `static_resources_overlay_interval` seconds if it is set. `StaticResources::set_overlay_directory` and `reload_overlay` do the same manually. `get_resource` on `StaticResources` still returns the embedded content, while `StaticContextManager::get_resource` honors the overlay.
+* In the hot-reload mode, a file is checked for modification every time it is requested. With the `watch` feature enabled, the files are watched with the file system notifications of the OS instead, so unchanged resources are served without touching the file system, and edits (including atomic saves which replace a file) show up on the next request. If the watcher cannot be started, it falls back to checking the modification times. The resources are not guarded by a global lock, so requests for unchanged resources never block each other, and reloading one file does not stall the requests for other files. Use `StaticContextManager::resources()` to access them directly.
+* `static_resources_initializer!(...).live_reload("/live-reload")` mounts a Server-Sent Events endpoint in the hot-reload mode, which sends a `reload` event whenever a resource is reloaded, and injects a small script into every `text/html` resource to reload the page on that event (or when the server comes back after a restart). In the embed mode, it does nothing.
+* The profile only decides the default mode: embed in the **release** profile and hot-reload in the **debug** profile. Enable the `embed` feature to include the files in a **debug** build (e.g. for integration tests which run from another directory), or the `hot-reload` feature to serve them from the disk in an optimized build. With both of them enabled, the files are included and the mode is selected when Rocket ignites, with `static_resources_initializer!(...).mode(ResourceMode::Embed)` or the `static_resources_mode` configuration (e.g. `ROCKET_STATIC_RESOURCES_MODE=hot-reload`).
+* `StaticContextManager::get_resource` returns a `Resource` view, whose methods (`bytes`, `as_str`, `mime`, `etag`, `len` and `modified`) are the same in every mode, so the content of a resource can be read without `#[cfg]` branches.
 
 See `examples`.
 */
@@ -57,20 +77,56 @@ pub extern crate mime;
 #[doc(hidden)]
 pub extern crate manifest_dir_macros;
 
+#[doc(hidden)]
+pub extern crate rocket_include_static_resources_macros;
+
+mod content_encoding;
+mod error;
+mod fairing;
+mod file_server;
+mod fingerprint;
 mod functions;
+mod key;
+mod metadata;
+mod mode;
+mod preconditions;
+mod range;
+mod resource;
+mod rewrite;
+mod sentinel;
+mod substitution;
+mod table;
 
 mod macros;
 
-#[cfg(debug_assertions)]
+#[cfg(hot_reload)]
 mod debug;
 
-#[cfg(not(debug_assertions))]
+#[cfg(embed)]
 mod release;
 
-#[cfg(debug_assertions)]
+#[cfg(all(embed, hot_reload))]
+mod dual;
+
+pub use content_encoding::*;
+#[cfg(all(embed, hot_reload))]
+pub use debug::FileResources;
+#[cfg(all(hot_reload, not(embed)))]
 pub use debug::*;
-#[cfg(not(debug_assertions))]
+#[cfg(all(embed, hot_reload))]
+pub use dual::*;
+pub use error::ResourceError;
+pub use file_server::EmbeddedFileServer;
+pub use key::ResourceKey;
+pub use metadata::ResourceMetadata;
+pub use mode::ResourceMode;
+pub use preconditions::{EntityTagCondition, Preconditions};
+#[cfg(all(embed, not(hot_reload)))]
 pub use release::*;
+#[cfg(all(embed, hot_reload))]
+pub use release::{StaticEntry, StaticResources};
+pub use resource::Resource;
 #[cfg(feature = "cache")]
 pub use rocket_cache_response::CacheResponse;
 pub use rocket_etag_if_none_match::{entity_tag::EntityTag, EtagIfNoneMatch};
+pub use sentinel::{NamedStaticResponse, ResourceName};
@@ -1,50 +1,159 @@
-/// Used for generating a fairing for static resources.
+/// Used for generating a fairing for static resources. Start with `fingerprint = "/base";` to also serve every resource from a fingerprinted URL under `/base`, or with `table;` to put the included resources into a table generated at compile time (see `static_resources_initialize_table!`).
 #[macro_export]
 macro_rules! static_resources_initializer {
-    ( $($name:expr => $path:expr), * $(,)* ) => {
+    ( fingerprint = $base:expr ; $($name:expr => $path:expr $(; { $($metadata:tt)* })?), * $(,)* ) => {
+        {
+            $crate::static_resources_initializer!(
+                $($name => $path $(; { $($metadata)* })?),*
+            )
+            .fingerprinted($base)
+        }
+    };
+    ( table ; $($name:expr => $path:expr $(; { $($metadata:tt)* })?), * $(,)* ) => {
+        {
+            $crate::StaticResponse::fairing(|resources| {
+                $crate::static_resources_initialize_table!(
+                    resources
+                    $(, $name => $path $(; { $($metadata)* })?)*
+                );
+            })
+        }
+    };
+    ( $($name:expr => $path:expr $(; { $($metadata:tt)* })?), * $(,)* ) => {
         {
             $crate::StaticResponse::fairing(|resources| {
                 $crate::static_resources_initialize!(
                     resources
-                    $(, $name => $path)*
+                    $(, $name => $path $(; { $($metadata)* })?)*
                 );
             })
         }
     };
 }
 
-/// Used for quickly creating **GET** route handlers to retrieve static resources.
+/// Used in the fairing of `StaticResponse` to register every file in a directory, under its path relative to the directory (separated by `/`) as the name. The directory is relative to the directory containing the manifest of your package. The `include` and `exclude` options are glob patterns matched against those relative paths, in which `*` does not match `/`, e.g. `include = "**/*.{js,css}"` for the scripts and stylesheets in every subdirectory.
+///
+/// The directory is listed at compile time, so a file added to it is found after the crate is rebuilt. Symbolic links are followed, except the ones to a directory which contains them.
+#[macro_export]
+macro_rules! static_resources_initialize_directory {
+    ( $resources:expr, $directory:expr $(, $option:ident = $glob:literal)* $(,)* ) => {
+        $crate::rocket_include_static_resources_macros::initialize_directory!($crate, $resources, $directory $(, $option = $glob)*)
+    };
+}
+
+/// Used for generating a fairing for static resources in a directory. See `static_resources_initialize_directory!`.
+#[macro_export]
+macro_rules! static_resources_directory {
+    ( $directory:expr $(, $option:ident = $glob:literal)* $(,)* ) => {
+        {
+            $crate::StaticResponse::fairing(|resources| {
+                $crate::static_resources_initialize_directory!(resources, $directory $(, $option = $glob)*);
+            })
+        }
+    };
+}
+
+/// Used for declaring the set of resource names as a module of constants, so that a misspelled name is a compile error wherever the constants are used instead of literal strings. Repeated names are also compile errors.
+///
+/// ```ignore
+/// static_resource_names! {
+///     pub mod names {
+///         FAVICON = "favicon",
+///         FAVICON_PNG = "favicon-png",
+///     }
+/// }
+/// ```
+///
+/// The module also contains `ALL`, which is a slice of all of the names.
+#[macro_export]
+macro_rules! static_resource_names {
+    ( $(#[$attr:meta])* $vis:vis mod $module:ident { $($(#[$constant_attr:meta])* $constant:ident = $name:literal), * $(,)* } ) => {
+        $(#[$attr])*
+        $vis mod $module {
+            $crate::rocket_include_static_resources_macros::check_unique_names!($($name),*);
+
+            $(
+                $(#[$constant_attr])*
+                pub const $constant: &str = $name;
+            )*
+
+            /// All of the names.
+            pub const ALL: &[&str] = &[$($name),*];
+        }
+    };
+}
+
+/// Used for generating a type of typed handles, with one variant for each resource, and the fairing which registers the resources in that order. A handle can be passed to `StaticContextManager::build` and `get_resource` instead of a name, and it is looked up by its index rather than by hashing the name.
+///
+/// ```ignore
+/// static_resources! {
+///     pub enum Resources {
+///         Favicon = "favicon" => "examples/front-end/images/favicon.ico",
+///         HtmlReadme = "html-readme" => ("examples", "front-end", "html", "README.html"),
+///     }
+/// }
+///
+/// rocket::build().attach(Resources::fairing())
+/// ```
+#[macro_export]
+macro_rules! static_resources {
+    ( $(#[$attr:meta])* $vis:vis enum $type:ident { $($(#[$variant_attr:meta])* $variant:ident = $name:literal => $path:expr $(; { $($metadata:tt)* })?), * $(,)* } ) => {
+        $(#[$attr])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        $vis enum $type {
+            $(
+                $(#[$variant_attr])*
+                $variant,
+            )*
+        }
+
+        impl $type {
+            /// All of the resources, in the order they are registered.
+            pub const ALL: &'static [$type] = &[$($type::$variant),*];
+
+            /// Get the name of the resource.
+            #[inline]
+            pub const fn name(self) -> &'static str {
+                match self {
+                    $($type::$variant => $name,)*
+                }
+            }
+
+            /// Create the fairing which registers all of the resources.
+            #[inline]
+            pub fn fairing() -> $crate::StaticResponseFairing {
+                $crate::static_resources_initializer!($($name => $path $(; { $($metadata)* })?),*)
+            }
+        }
+
+        impl $crate::ResourceKey for $type {
+            #[inline]
+            fn name(&self) -> &str {
+                $type::name(*self)
+            }
+
+            #[inline]
+            fn index(&self) -> Option<usize> {
+                Some(*self as usize)
+            }
+        }
+    };
+}
+
+/// Used for quickly creating **GET** route handlers to retrieve static resources. If a name is a literal string, or a path to a constant or a typed handle, the handler responds a `NamedStaticResponse`, whose sentinel aborts the launch if the resource is not registered.
 #[macro_export]
 macro_rules! static_response_handler {
     ( $($route:expr => $handler_name:ident => $name:expr), * $(,)* ) => {
-        $(
-            #[get($route)]
-            fn $handler_name(
-                static_resources: &$crate::rocket::State<$crate::StaticContextManager>,
-                etag_if_none_match: $crate::EtagIfNoneMatch,
-            ) -> $crate::StaticResponse {
-                static_resources.build(&etag_if_none_match, $name)
-            }
-        )*
+        $crate::rocket_include_static_resources_macros::static_response_handlers!($crate, $($route => $handler_name => $name),*);
     };
 }
 
 #[cfg(feature = "cache")]
-/// Used for quickly creating **GET** route handlers to retrieve static resources with cache control.
+/// Used for quickly creating **GET** route handlers to retrieve static resources with cache control. If a name is a literal string, or a path to a constant or a typed handle, the handler responds a `NamedStaticResponse`, whose sentinel aborts the launch if the resource is not registered.
 #[macro_export]
 macro_rules! cached_static_response_handler {
     ( $max_age:expr, $must_revalidate:expr ; $($route:expr => $handler_name:ident => $name:expr), * $(,)* ) => {
-        $(
-            #[get($route)]
-            fn $handler_name(
-                static_resources: &$crate::rocket::State<$crate::StaticContextManager>,
-                etag_if_none_match: $crate::EtagIfNoneMatch,
-            ) -> $crate::CacheResponse<$crate::StaticResponse> {
-                let responder =  static_resources.build(&etag_if_none_match, $name);
-
-                $crate::CacheResponse::public_only_release(responder, $max_age, $must_revalidate)
-            }
-        )*
+        $crate::rocket_include_static_resources_macros::static_response_handlers!($crate, cache($max_age, $must_revalidate); $($route => $handler_name => $name),*);
     };
     ( $max_age:expr ; $($route:expr => $handler_name:ident => $name:expr), * $(,)* ) => {
         $crate::cached_static_response_handler! {
@@ -55,3 +164,64 @@ macro_rules! cached_static_response_handler {
         }
     };
 }
+
+/// Repeated names are compile errors, whether they are repeated in one invocation:
+///
+/// ```compile_fail
+/// rocket_include_static_resources::StaticResponse::fairing(|resources| {
+///     rocket_include_static_resources::static_resources_initialize!(
+///         resources,
+///         "readme" => "examples/front-end/html/README.html",
+///         "readme" => "examples/front-end/images/favicon.ico",
+///     );
+/// });
+/// ```
+///
+/// ```compile_fail
+/// rocket_include_static_resources::static_resource_names! {
+///     mod names {
+///         README = "readme",
+///         FAVICON = "readme",
+///     }
+/// }
+/// ```
+///
+/// or registered by an explicit entry and found in a directory in the same fairing:
+///
+/// ```compile_fail,E0428
+/// rocket_include_static_resources::StaticResponse::fairing(|resources| {
+///     rocket_include_static_resources::static_resources_initialize!(
+///         resources,
+///         "README.html" => "examples/front-end/html/README.html",
+///     );
+///     rocket_include_static_resources::static_resources_initialize_directory!(
+///         resources,
+///         "examples/front-end/html"
+///     );
+/// });
+/// ```
+///
+/// Unique names compile:
+///
+/// ```
+/// rocket_include_static_resources::StaticResponse::fairing(|resources| {
+///     rocket_include_static_resources::static_resources_initialize!(
+///         resources,
+///         "readme" => "examples/front-end/html/README.html",
+///         "favicon" => "examples/front-end/images/favicon.ico",
+///     );
+///     rocket_include_static_resources::static_resources_initialize_directory!(
+///         resources,
+///         "examples/front-end/html"
+///     );
+/// });
+///
+/// rocket_include_static_resources::static_resource_names! {
+///     mod names {
+///         README = "readme",
+///         FAVICON = "favicon",
+///     }
+/// }
+/// ```
+#[cfg(doctest)]
+struct RepeatedNames;
@@ -0,0 +1,45 @@
+use crate::rocket::response::Builder;
+
+/// Metadata of a resource given in the initializer, e.g. `"feed" => "data/feed.xml"; { mime: "application/atom+xml", charset: "utf-8", headers: { "X-Robots-Tag": "noindex" } }`. The macros check it at compile time.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ResourceMetadata {
+    mime:    Option<&'static str>,
+    headers: &'static [(&'static str, &'static str)],
+}
+
+impl ResourceMetadata {
+    /// No metadata. The MIME type is guessed from the extension of the file.
+    pub const NONE: ResourceMetadata = ResourceMetadata::new(None, &[]);
+
+    /// Create metadata with the MIME type (including its parameters, such as the charset) which overrides the guessed one, and the extra headers to respond.
+    #[inline]
+    pub const fn new(
+        mime: Option<&'static str>,
+        headers: &'static [(&'static str, &'static str)],
+    ) -> ResourceMetadata {
+        ResourceMetadata {
+            mime,
+            headers,
+        }
+    }
+
+    /// Get the MIME type which overrides the guessed one.
+    #[inline]
+    pub const fn mime(&self) -> Option<&'static str> {
+        self.mime
+    }
+
+    /// Get the extra headers.
+    #[inline]
+    pub const fn headers(&self) -> &'static [(&'static str, &'static str)] {
+        self.headers
+    }
+
+    /// Add the extra headers to a response.
+    #[inline]
+    pub(crate) fn apply_headers(&self, response: &mut Builder<'_>) {
+        for &(name, value) in self.headers {
+            response.raw_header_adjoin(name, value);
+        }
+    }
+}
@@ -0,0 +1,104 @@
+use std::{
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
+
+use crate::rocket::{Build, Rocket};
+
+/// The key of the Rocket configuration which selects the mode, e.g. `ROCKET_STATIC_RESOURCES_MODE=embed`.
+const CONFIG_KEY: &str = "static_resources_mode";
+
+/// How static resources are served.
+///
+/// Which modes are available is decided at compile time. By default, it is **embed** in the **release** profile and **hot-reload** in the **debug** profile. The `embed` and `hot-reload` features override that, and if both of them are enabled, the mode is selected when Rocket ignites, with `StaticResponseFairing::mode` or the `static_resources_mode` configuration (defaulting to the profile-based mode).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ResourceMode {
+    /// Serve the contents included into the executable binary file at compile time.
+    Embed,
+    /// Serve the files and reload them when they are modified.
+    HotReload,
+}
+
+impl ResourceMode {
+    /// The modes compiled into this build.
+    pub const AVAILABLE: &'static [ResourceMode] = &[
+        #[cfg(embed)]
+        ResourceMode::Embed,
+        #[cfg(hot_reload)]
+        ResourceMode::HotReload,
+    ];
+
+    /// Get the name of the mode, which is also accepted by the `static_resources_mode` configuration.
+    #[inline]
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            ResourceMode::Embed => "embed",
+            ResourceMode::HotReload => "hot-reload",
+        }
+    }
+
+    /// Check whether the mode is compiled into this build.
+    #[inline]
+    pub fn is_available(&self) -> bool {
+        Self::AVAILABLE.contains(self)
+    }
+
+    /// Select the mode from the explicitly given one, the configuration of Rocket, or the default one, in that order.
+    pub(crate) fn select(
+        explicit: Option<ResourceMode>,
+        rocket: &Rocket<Build>,
+    ) -> Result<ResourceMode, String> {
+        let mode = match explicit {
+            Some(mode) => mode,
+            None => match rocket.figment().extract_inner::<String>(CONFIG_KEY) {
+                Ok(mode) => mode.parse()?,
+                Err(_) => ResourceMode::default(),
+            },
+        };
+
+        if mode.is_available() {
+            Ok(mode)
+        } else {
+            Err(format!(
+                "The {} mode of static resources is not compiled in. Enable the `{}` feature of \
+                 `rocket-include-static-resources`.",
+                mode, mode
+            ))
+        }
+    }
+}
+
+impl Default for ResourceMode {
+    /// **hot-reload** if it is available and this is a **debug** build, otherwise **embed** if it is available.
+    #[inline]
+    fn default() -> Self {
+        if cfg!(all(hot_reload, any(debug_assertions, not(embed)))) {
+            ResourceMode::HotReload
+        } else {
+            ResourceMode::Embed
+        }
+    }
+}
+
+impl Display for ResourceMode {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ResourceMode {
+    type Err = String;
+
+    #[inline]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "embed" => Ok(ResourceMode::Embed),
+            "hot-reload" | "hot_reload" => Ok(ResourceMode::HotReload),
+            _ => Err(format!(
+                "`{}` is not a mode of static resources. It should be `embed` or `hot-reload`.",
+                s
+            )),
+        }
+    }
+}
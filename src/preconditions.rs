@@ -0,0 +1,349 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::{
+    rocket::{
+        http::{Method, Status},
+        outcome::Outcome,
+        request::{self, FromRequest, Request},
+    },
+    EntityTag, EtagIfNoneMatch,
+};
+
+/// The value of an `If-Match` or `If-None-Match` header.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum EntityTagCondition {
+    /// `*`
+    Any,
+    /// A list of entity-tags. An empty list, which a malformed `If-Match` header becomes, matches nothing.
+    Tags(Vec<EntityTag<'static>>),
+}
+
+impl EntityTagCondition {
+    /// Parse the value of a header. `None` means the value is malformed.
+    fn parse(value: &str) -> Option<EntityTagCondition> {
+        let value = value.trim();
+
+        if value == "*" {
+            return Some(EntityTagCondition::Any);
+        }
+
+        let mut tags = Vec::new();
+        let mut rest = value;
+
+        loop {
+            rest = rest.trim_start_matches(|c: char| c == ',' || c.is_ascii_whitespace());
+
+            if rest.is_empty() {
+                break;
+            }
+
+            // an entity-tag is either `"..."` or `W/"..."`, and the opaque tag cannot contain a double quote
+            let end = match rest.strip_prefix("W/").unwrap_or(rest).strip_prefix('"') {
+                Some(quoted) => rest.len() - quoted.len() + quoted.find('"')? + 1,
+                None => rest.find(',').unwrap_or(rest.len()),
+            };
+
+            tags.push(EntityTag::from_str(rest[..end].trim_end()).ok()?.into_owned());
+
+            rest = &rest[end..];
+        }
+
+        if tags.is_empty() {
+            None
+        } else {
+            Some(EntityTagCondition::Tags(tags))
+        }
+    }
+
+    #[inline]
+    fn strong_matches(&self, etag: &EntityTag) -> bool {
+        match self {
+            EntityTagCondition::Any => true,
+            EntityTagCondition::Tags(tags) => tags.iter().any(|tag| tag.strong_eq(etag)),
+        }
+    }
+
+    #[inline]
+    fn weak_matches(&self, etag: &EntityTag) -> bool {
+        match self {
+            EntityTagCondition::Any => true,
+            EntityTagCondition::Tags(tags) => tags.iter().any(|tag| tag.weak_eq(etag)),
+        }
+    }
+}
+
+/// The request guard used for getting the `If-Match`, `If-None-Match`, `If-Modified-Since` and `If-Unmodified-Since` headers. They are evaluated in the order defined in [RFC 9110](https://www.rfc-editor.org/rfc/rfc9110#section-13.2.2). `If-Range` is evaluated along with `Range` when responding.
+#[derive(Debug, Clone, Default)]
+pub struct Preconditions {
+    pub if_match:            Option<EntityTagCondition>,
+    pub if_none_match:       Option<EntityTagCondition>,
+    pub if_modified_since:   Option<SystemTime>,
+    pub if_unmodified_since: Option<SystemTime>,
+}
+
+impl Preconditions {
+    /// Get the conditional headers of a request.
+    pub fn from_request_headers(request: &Request<'_>) -> Preconditions {
+        let headers = request.headers();
+
+        let date =
+            |name| headers.get_one(name).and_then(|date| httpdate::parse_http_date(date).ok());
+
+        Preconditions {
+            // a malformed `If-Match` must never match, while a malformed `If-None-Match` is ignored
+            if_match:            headers.get_one("If-Match").map(|if_match| {
+                EntityTagCondition::parse(if_match)
+                    .unwrap_or_else(|| EntityTagCondition::Tags(Vec::new()))
+            }),
+            if_none_match:       headers
+                .get_one("If-None-Match")
+                .and_then(EntityTagCondition::parse),
+            if_modified_since:   date("If-Modified-Since"),
+            if_unmodified_since: date("If-Unmodified-Since"),
+        }
+    }
+
+    /// Evaluate the preconditions against the selected representation. `None` means the request should be processed normally.
+    pub(crate) fn evaluate(
+        &self,
+        method: Method,
+        etag: &EntityTag,
+        last_modified: Option<SystemTime>,
+    ) -> Option<Status> {
+        let safe = matches!(method, Method::Get | Method::Head);
+
+        if let Some(if_match) = &self.if_match {
+            if !if_match.strong_matches(etag) {
+                return Some(Status::PreconditionFailed);
+            }
+        } else if let (Some(date), Some(last_modified)) = (self.if_unmodified_since, last_modified)
+        {
+            if unix_seconds(last_modified) > unix_seconds(date) {
+                return Some(Status::PreconditionFailed);
+            }
+        }
+
+        if let Some(if_none_match) = &self.if_none_match {
+            if if_none_match.weak_matches(etag) {
+                return Some(if safe { Status::NotModified } else { Status::PreconditionFailed });
+            }
+        } else if let (true, Some(date), Some(last_modified)) =
+            (safe, self.if_modified_since, last_modified)
+        {
+            if unix_seconds(last_modified) <= unix_seconds(date) {
+                return Some(Status::NotModified);
+            }
+        }
+
+        None
+    }
+}
+
+impl From<&EtagIfNoneMatch<'_>> for Preconditions {
+    #[inline]
+    fn from(etag_if_none_match: &EtagIfNoneMatch<'_>) -> Self {
+        Preconditions {
+            if_none_match: etag_if_none_match
+                .etag
+                .clone()
+                .map(|etag| EntityTagCondition::Tags(vec![etag.into_owned()])),
+            ..Preconditions::default()
+        }
+    }
+}
+
+impl From<&Preconditions> for Preconditions {
+    #[inline]
+    fn from(preconditions: &Preconditions) -> Self {
+        preconditions.clone()
+    }
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for Preconditions {
+    type Error = ();
+
+    async fn from_request(request: &'r Request<'_>) -> request::Outcome<Self, Self::Error> {
+        Outcome::Success(Preconditions::from_request_headers(request))
+    }
+}
+
+/// HTTP dates have a resolution of one second.
+#[inline]
+pub(crate) fn unix_seconds(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH).map(|duration| duration.as_secs()).unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use super::*;
+
+    fn etag(etag: &str) -> EntityTag<'static> {
+        EntityTag::from_str(etag).unwrap().into_owned()
+    }
+
+    fn tags(etags: &[&str]) -> Option<EntityTagCondition> {
+        Some(EntityTagCondition::Tags(etags.iter().map(|tag| etag(tag)).collect()))
+    }
+
+    fn time(seconds: u64) -> Option<SystemTime> {
+        Some(UNIX_EPOCH + Duration::from_secs(seconds))
+    }
+
+    #[test]
+    fn parse_conditions() {
+        assert_eq!(Some(EntityTagCondition::Any), EntityTagCondition::parse(" * "));
+        assert_eq!(tags(&["\"a\""]), EntityTagCondition::parse("\"a\""));
+        assert_eq!(
+            tags(&["\"a\"", "W/\"b\"", "\"c,d\""]),
+            EntityTagCondition::parse("\"a\", W/\"b\",,\"c,d\"")
+        );
+        assert_eq!(None, EntityTagCondition::parse(""));
+        assert_eq!(None, EntityTagCondition::parse("a"));
+        assert_eq!(None, EntityTagCondition::parse("\"a\", b"));
+        assert_eq!(None, EntityTagCondition::parse("\"a"));
+    }
+
+    #[test]
+    fn compare_strongly_and_weakly() {
+        let strong = etag("\"a\"");
+        let weak = etag("W/\"a\"");
+
+        let if_match = |condition| Preconditions {
+            if_match: condition,
+            ..Preconditions::default()
+        };
+
+        assert_eq!(None, if_match(tags(&["\"a\""])).evaluate(Method::Get, &strong, None));
+        assert_eq!(
+            Some(Status::PreconditionFailed),
+            if_match(tags(&["W/\"a\""])).evaluate(Method::Get, &strong, None)
+        );
+        assert_eq!(
+            Some(Status::PreconditionFailed),
+            if_match(tags(&["\"a\""])).evaluate(Method::Get, &weak, None)
+        );
+
+        let if_none_match = |condition| Preconditions {
+            if_none_match: condition,
+            ..Preconditions::default()
+        };
+
+        assert_eq!(
+            Some(Status::NotModified),
+            if_none_match(tags(&["W/\"a\""])).evaluate(Method::Get, &strong, None)
+        );
+        assert_eq!(
+            Some(Status::NotModified),
+            if_none_match(tags(&["\"b\"", "\"a\""])).evaluate(Method::Get, &weak, None)
+        );
+        assert_eq!(None, if_none_match(tags(&["\"b\""])).evaluate(Method::Get, &strong, None));
+    }
+
+    #[test]
+    fn match_any() {
+        let etag = etag("W/\"a\"");
+
+        let preconditions = Preconditions {
+            if_match: Some(EntityTagCondition::Any),
+            ..Preconditions::default()
+        };
+
+        assert_eq!(None, preconditions.evaluate(Method::Get, &etag, None));
+
+        let preconditions = Preconditions {
+            if_none_match: Some(EntityTagCondition::Any),
+            ..Preconditions::default()
+        };
+
+        assert_eq!(Some(Status::NotModified), preconditions.evaluate(Method::Get, &etag, None));
+    }
+
+    #[test]
+    fn never_match_malformed_if_match() {
+        let preconditions = Preconditions {
+            if_match: Some(EntityTagCondition::Tags(Vec::new())),
+            ..Preconditions::default()
+        };
+
+        assert_eq!(
+            Some(Status::PreconditionFailed),
+            preconditions.evaluate(Method::Get, &etag("\"a\""), None)
+        );
+    }
+
+    #[test]
+    fn if_match_over_if_unmodified_since() {
+        let etag = etag("\"a\"");
+
+        // `If-Unmodified-Since` would fail, but `If-Match` takes precedence
+        let preconditions = Preconditions {
+            if_match: tags(&["\"a\""]),
+            if_unmodified_since: time(100),
+            ..Preconditions::default()
+        };
+
+        assert_eq!(None, preconditions.evaluate(Method::Get, &etag, time(200)));
+
+        let preconditions = Preconditions {
+            if_unmodified_since: time(100),
+            ..Preconditions::default()
+        };
+
+        assert_eq!(
+            Some(Status::PreconditionFailed),
+            preconditions.evaluate(Method::Get, &etag, time(200))
+        );
+        assert_eq!(None, preconditions.evaluate(Method::Get, &etag, time(100)));
+        assert_eq!(None, preconditions.evaluate(Method::Get, &etag, None));
+    }
+
+    #[test]
+    fn if_none_match_over_if_modified_since() {
+        let etag = etag("\"a\"");
+
+        // `If-Modified-Since` would hold, but `If-None-Match` takes precedence
+        let preconditions = Preconditions {
+            if_none_match: tags(&["\"b\""]),
+            if_modified_since: time(200),
+            ..Preconditions::default()
+        };
+
+        assert_eq!(None, preconditions.evaluate(Method::Get, &etag, time(100)));
+
+        let preconditions = Preconditions {
+            if_modified_since: time(200),
+            ..Preconditions::default()
+        };
+
+        assert_eq!(
+            Some(Status::NotModified),
+            preconditions.evaluate(Method::Get, &etag, time(100))
+        );
+        assert_eq!(None, preconditions.evaluate(Method::Get, &etag, time(300)));
+        assert_eq!(None, preconditions.evaluate(Method::Post, &etag, time(100)));
+    }
+
+    #[test]
+    fn not_modified_only_for_get_and_head() {
+        let etag = etag("\"a\"");
+
+        let preconditions = Preconditions {
+            if_none_match: tags(&["\"a\""]),
+            ..Preconditions::default()
+        };
+
+        assert_eq!(Some(Status::NotModified), preconditions.evaluate(Method::Get, &etag, None));
+        assert_eq!(Some(Status::NotModified), preconditions.evaluate(Method::Head, &etag, None));
+        assert_eq!(
+            Some(Status::PreconditionFailed),
+            preconditions.evaluate(Method::Post, &etag, None)
+        );
+        assert_eq!(
+            Some(Status::PreconditionFailed),
+            preconditions.evaluate(Method::Delete, &etag, None)
+        );
+    }
+}
@@ -0,0 +1,277 @@
+use std::{ops::Range, time::SystemTime};
+
+use crate::{preconditions::unix_seconds, rocket::request::Request, EntityTag};
+
+/// Requests with more ranges than this are served in full.
+const MAX_RANGES: usize = 64;
+
+/// How a request should be answered with respect to its `Range` header.
+#[derive(Debug)]
+pub(crate) enum RangeResponse {
+    /// Send the whole representation with `200 OK`.
+    Full,
+    /// Send a single part with `206 Partial Content`.
+    Single(Range<usize>),
+    /// Send several parts with `206 Partial Content` as `multipart/byteranges`. The first field is the `Content-Type` value of the response.
+    Multiple(String, Vec<u8>),
+    /// Send `416 Range Not Satisfiable`.
+    Unsatisfiable,
+}
+
+impl RangeResponse {
+    /// Evaluate the `Range` and `If-Range` headers of a request against a representation.
+    pub(crate) fn evaluate(
+        request: &Request<'_>,
+        data: &[u8],
+        mime: &str,
+        etag: &EntityTag<'_>,
+        last_modified: Option<SystemTime>,
+    ) -> RangeResponse {
+        let range = match request.headers().get_one("Range") {
+            Some(range) => range,
+            None => return RangeResponse::Full,
+        };
+
+        if !if_range_matches(request.headers().get_one("If-Range"), etag, last_modified) {
+            return RangeResponse::Full;
+        }
+
+        let ranges = match parse_ranges(range, data.len()) {
+            Some(ranges) => ranges,
+            None => return RangeResponse::Full,
+        };
+
+        match ranges.len() {
+            0 => RangeResponse::Unsatisfiable,
+            1 => RangeResponse::Single(ranges.into_iter().next().unwrap()),
+            _ => {
+                let mut boundary = String::from("RocketIncludeStaticResources");
+
+                boundary.extend(etag.get_tag().chars().filter(char::is_ascii_alphanumeric));
+
+                RangeResponse::Multiple(
+                    format!("multipart/byteranges; boundary={}", boundary),
+                    multipart_body(data, &ranges, mime, &boundary),
+                )
+            },
+        }
+    }
+}
+
+/// Format the value of a `Content-Range` header.
+#[inline]
+pub(crate) fn content_range(range: &Range<usize>, len: usize) -> String {
+    format!("bytes {}-{}/{}", range.start, range.end - 1, len)
+}
+
+/// Format the value of a `Content-Range` header for a `416 Range Not Satisfiable` response.
+#[inline]
+pub(crate) fn unsatisfied_content_range(len: usize) -> String {
+    format!("bytes */{}", len)
+}
+
+/// `If-Range` only holds when it strongly matches the entity tag, or exactly matches the last modification date.
+fn if_range_matches(
+    if_range: Option<&str>,
+    etag: &EntityTag<'_>,
+    last_modified: Option<SystemTime>,
+) -> bool {
+    let if_range = match if_range {
+        Some(if_range) => if_range.trim(),
+        None => return true,
+    };
+
+    if if_range.starts_with('"') || if_range.starts_with("W/") {
+        match EntityTag::from_str(if_range) {
+            Ok(if_range) => if_range.strong_eq(etag),
+            Err(_) => false,
+        }
+    } else {
+        match (httpdate::parse_http_date(if_range), last_modified) {
+            (Ok(date), Some(last_modified)) => unix_seconds(date) == unix_seconds(last_modified),
+            _ => false,
+        }
+    }
+}
+
+/// Parse the value of a `Range` header. `None` means the header is invalid (or has too many ranges) and should be ignored, and an empty `Vec` means no range is satisfiable. Overlapping and adjacent ranges are merged.
+fn parse_ranges(range: &str, len: usize) -> Option<Vec<Range<usize>>> {
+    let (unit, specs) = range.split_once('=')?;
+
+    if !unit.trim().eq_ignore_ascii_case("bytes") {
+        return None;
+    }
+
+    let mut ranges = Vec::new();
+    let mut count = 0;
+
+    for spec in specs.split(',').map(str::trim).filter(|spec| !spec.is_empty()) {
+        if count == MAX_RANGES {
+            return None;
+        }
+
+        count += 1;
+
+        let (first, last) = spec.split_once('-')?;
+
+        let (first, last) = (first.trim(), last.trim());
+
+        let range = if first.is_empty() {
+            let suffix = last.parse::<usize>().ok()?;
+
+            if suffix == 0 || len == 0 {
+                continue;
+            }
+
+            len.saturating_sub(suffix)..len
+        } else {
+            let first = first.parse::<usize>().ok()?;
+
+            let end = if last.is_empty() {
+                len
+            } else {
+                let last = last.parse::<usize>().ok()?;
+
+                if last < first {
+                    return None;
+                }
+
+                last.saturating_add(1).min(len)
+            };
+
+            if first >= len {
+                continue;
+            }
+
+            first..end
+        };
+
+        ranges.push(range);
+    }
+
+    // a range set needs at least one range spec
+    if count == 0 {
+        return None;
+    }
+
+    ranges.sort_by_key(|range| range.start);
+
+    let mut merged: Vec<Range<usize>> = Vec::with_capacity(ranges.len());
+
+    for range in ranges {
+        match merged.last_mut() {
+            Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+            _ => merged.push(range),
+        }
+    }
+
+    Some(merged)
+}
+
+fn multipart_body(data: &[u8], ranges: &[Range<usize>], mime: &str, boundary: &str) -> Vec<u8> {
+    let mut body = Vec::with_capacity(ranges.iter().map(|range| range.len() + 128).sum());
+
+    for range in ranges {
+        body.extend_from_slice(b"\r\n--");
+        body.extend_from_slice(boundary.as_bytes());
+        body.extend_from_slice(b"\r\nContent-Type: ");
+        body.extend_from_slice(mime.as_bytes());
+        body.extend_from_slice(b"\r\nContent-Range: ");
+        body.extend_from_slice(content_range(range, data.len()).as_bytes());
+        body.extend_from_slice(b"\r\n\r\n");
+        body.extend_from_slice(&data[range.clone()]);
+    }
+
+    body.extend_from_slice(b"\r\n--");
+    body.extend_from_slice(boundary.as_bytes());
+    body.extend_from_slice(b"--\r\n");
+
+    body
+}
+
+#[cfg(test)]
+#[allow(clippy::single_range_in_vec_init)]
+mod tests {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use super::*;
+
+    #[test]
+    fn parse_single_ranges() {
+        assert_eq!(Some(vec![0..10]), parse_ranges("bytes=0-9", 100));
+        assert_eq!(Some(vec![90..100]), parse_ranges("bytes=90-", 100));
+        assert_eq!(Some(vec![0..100]), parse_ranges("BYTES = 0-", 100));
+        assert_eq!(Some(vec![95..100]), parse_ranges("bytes=95-200", 100));
+    }
+
+    #[test]
+    fn parse_suffix_ranges() {
+        assert_eq!(Some(vec![90..100]), parse_ranges("bytes=-10", 100));
+        assert_eq!(Some(vec![0..100]), parse_ranges("bytes=-1000", 100));
+        assert_eq!(Some(vec![]), parse_ranges("bytes=-0", 100));
+        assert_eq!(Some(vec![]), parse_ranges("bytes=-10", 0));
+    }
+
+    #[test]
+    fn parse_ranges_past_the_end() {
+        assert_eq!(Some(vec![]), parse_ranges("bytes=100-", 100));
+        assert_eq!(Some(vec![]), parse_ranges("bytes=100-200", 100));
+        assert_eq!(Some(vec![0..1]), parse_ranges("bytes=100-200, 0-0", 100));
+    }
+
+    #[test]
+    fn merge_overlapping_ranges() {
+        assert_eq!(Some(vec![0..30]), parse_ranges("bytes=10-29, 0-14", 100));
+        assert_eq!(Some(vec![0..20]), parse_ranges("bytes=0-9,10-19", 100));
+        assert_eq!(Some(vec![0..10, 50..60]), parse_ranges("bytes=50-59, 0-9, 5-7", 100));
+        assert_eq!(Some(vec![0..10, 90..100]), parse_ranges("bytes=-10, 0-9", 100));
+    }
+
+    #[test]
+    fn ignore_invalid_ranges() {
+        assert_eq!(None, parse_ranges("bytes=", 100));
+        assert_eq!(None, parse_ranges("bytes= , ", 100));
+        assert_eq!(None, parse_ranges("bytes", 100));
+        assert_eq!(None, parse_ranges("items=0-9", 100));
+        assert_eq!(None, parse_ranges("bytes=9-0", 100));
+        assert_eq!(None, parse_ranges("bytes=a-9", 100));
+        assert_eq!(None, parse_ranges("bytes=0-9,10", 100));
+        assert_eq!(None, parse_ranges("bytes=-", 100));
+    }
+
+    #[test]
+    fn ignore_too_many_ranges() {
+        let specs = |n: usize| (0..n).map(|i| format!("{}-{}", i * 2, i * 2)).collect::<Vec<_>>();
+
+        let range = format!("bytes={}", specs(MAX_RANGES).join(","));
+
+        assert_eq!(MAX_RANGES, parse_ranges(&range, 1000).unwrap().len());
+
+        let range = format!("bytes={}", specs(MAX_RANGES + 1).join(","));
+
+        assert_eq!(None, parse_ranges(&range, 1000));
+    }
+
+    #[test]
+    fn match_if_range() {
+        let etag = EntityTag::from_str("\"abc\"").unwrap();
+        let last_modified = UNIX_EPOCH + Duration::from_millis(784_111_777_500);
+
+        assert!(if_range_matches(None, &etag, None));
+        assert!(if_range_matches(Some("\"abc\""), &etag, None));
+        assert!(!if_range_matches(Some("\"xyz\""), &etag, None));
+        assert!(!if_range_matches(Some("W/\"abc\""), &etag, None));
+        assert!(!if_range_matches(Some("\"abc"), &etag, None));
+
+        let weak = EntityTag::from_str("W/\"abc\"").unwrap();
+
+        assert!(!if_range_matches(Some("W/\"abc\""), &weak, None));
+
+        let date = "Sun, 06 Nov 1994 08:49:37 GMT";
+
+        assert!(if_range_matches(Some(date), &etag, Some(last_modified)));
+        assert!(!if_range_matches(Some(date), &etag, None));
+        assert!(!if_range_matches(Some(date), &etag, Some(last_modified + Duration::from_secs(1))));
+        assert!(!if_range_matches(Some("yesterday"), &etag, Some(last_modified)));
+    }
+}
@@ -1,7 +1,11 @@
-use super::{StaticContextManager, StaticResources, StaticResponse};
-use crate::rocket::{
-    fairing::{Fairing, Info, Kind},
-    Build, Rocket,
+use super::{overlay, StaticContextManager, StaticResources, StaticResponse};
+use crate::{
+    fairing::FairingOptions,
+    rocket::{
+        fairing::{Fairing, Info, Kind},
+        Build, Orbit, Rocket,
+    },
+    ResourceMode,
 };
 
 const FAIRING_NAME: &str = "Static Resources";
@@ -9,6 +13,7 @@ const FAIRING_NAME: &str = "Static Resources";
 /// The fairing of `StaticResponse`.
 pub struct StaticResponseFairing {
     pub(crate) custom_callback: Box<dyn Fn(&mut StaticResources) + Send + Sync + 'static>,
+    pub(crate) options:         FairingOptions,
 }
 
 #[rocket::async_trait]
@@ -16,30 +21,75 @@ impl Fairing for StaticResponseFairing {
     #[inline]
     fn info(&self) -> Info {
         Info {
-            name: FAIRING_NAME, kind: Kind::Ignite
+            name: FAIRING_NAME, kind: Kind::Ignite | Kind::Liftoff
         }
     }
 
     #[inline]
     async fn on_ignite(&self, rocket: Rocket<Build>) -> Result<Rocket<Build>, Rocket<Build>> {
+        // only one mode is compiled in, so this fails if another one is selected
+        let checked =
+            ResourceMode::select(self.options.mode, &rocket).and_then(|_| self.options.check());
+
+        if let Err(message) = checked {
+            rocket::error!("{}", message);
+
+            return Err(rocket);
+        }
+
         let mut resources = StaticResources::new();
 
         (self.custom_callback)(&mut resources);
 
-        let state = StaticContextManager::new(resources);
+        if let Err(message) = self
+            .options
+            .substitute(&rocket, |name, substitutions| resources.substitute(name, substitutions))
+        {
+            rocket::error!("{}", message);
+
+            return Err(rocket);
+        }
+
+        if let (Some(base), Some(fingerprint_base)) =
+            (self.options.rewrite_base.as_deref(), self.options.fingerprint_base.as_deref())
+        {
+            resources.rewrite_references(base, fingerprint_base);
+        }
+
+        if let Err(message) = overlay::configure(&rocket, &mut resources) {
+            rocket::error!("{}", message);
 
-        Ok(rocket.manage(state))
+            return Err(rocket);
+        }
+
+        let state = StaticContextManager::new(resources, self.options.fingerprint_base.clone());
+
+        let rocket = rocket.manage(state);
+
+        Ok(self.options.mount(rocket, ResourceMode::Embed))
+    }
+
+    #[inline]
+    async fn on_liftoff(&self, rocket: &Rocket<Orbit>) {
+        if let Some(overlay) =
+            rocket.state::<StaticContextManager>().and_then(|state| state.resources().overlay())
+        {
+            overlay::watch(rocket, overlay.clone());
+        }
     }
 }
 
 impl StaticResponse {
     #[inline]
     /// Create the fairing of `HandlebarsResponse`.
-    pub fn fairing<F>(f: F) -> impl Fairing
+    pub fn fairing<F>(f: F) -> StaticResponseFairing
     where
         F: Fn(&mut StaticResources) + Send + Sync + 'static, {
         StaticResponseFairing {
-            custom_callback: Box::new(f)
+            custom_callback: Box::new(f),
+            options:         FairingOptions::default(),
         }
     }
 }
+
+crate::fairing::impl_fairing_options!();
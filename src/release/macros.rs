@@ -1,9 +1,27 @@
-/// Used in the fairing of `StaticResponse` to include static files into your executable binary file. You need to specify each file's name and its path relative to the directory containing the manifest of your package. In order to reduce the compilation time and allow to hot-reload resources, files are compiled into your executable binary file together, only when you are using the **release** profile.
+/// Used in the fairing of `StaticResponse` to include static files into your executable binary file. You need to specify each file's name and its path relative to the directory containing the manifest of your package. In order to reduce the compilation time and allow to hot-reload resources, files are compiled into your executable binary file together, only when you are using the **release** profile or the `embed` feature. The last modification date of the resources is the time they are built. With the `compression` feature enabled, gzip, brotli and zstd representations of each file are also compressed and included at compile time.
 #[macro_export]
 macro_rules! static_resources_initialize {
-    ( $resources:expr, $($name:expr => $path:expr), * $(,)* ) => {
+    ( $resources:expr, $($name:expr => $path:expr $(; { $($metadata:tt)* })?), * $(,)* ) => {
+        $crate::rocket_include_static_resources_macros::check_unique_names!($($name),*);
+
+        let (timestamp, http_date) = $crate::rocket_include_static_resources_macros::build_time!();
+
+        $resources.set_last_modified_precomputed(::std::time::UNIX_EPOCH + ::std::time::Duration::from_secs(timestamp), http_date);
+
         $(
-            $resources.register_resource_static($name, $crate::manifest_dir_macros::mime_guess!(default = "application/octet-stream", $path).parse().unwrap_or($crate::mime::APPLICATION_OCTET_STREAM), include_bytes!($crate::manifest_dir_macros::path!($path)));
+            $resources.register_resource_static_precomputed($name, $crate::manifest_dir_macros::mime_guess!(default = "application/octet-stream", $path), include_bytes!($crate::manifest_dir_macros::path!($path)), $crate::rocket_include_static_resources_macros::include_etag!($path), $crate::rocket_include_static_resources_macros::include_digests!($path), $crate::rocket_include_static_resources_macros::include_encoded!($crate, $path), $crate::rocket_include_static_resources_macros::resource_metadata!($crate, $path $(, { $($metadata)* })?));
         )*
     };
 }
+
+/// Used in the fairing of `StaticResponse` like `static_resources_initialize!`, but the resources are put into a table generated at compile time, which is sorted by name, instead of being registered one by one when Rocket ignites. The names must be literal strings.
+#[macro_export]
+macro_rules! static_resources_initialize_table {
+    ( $resources:expr, $($name:expr => $path:expr $(; { $($metadata:tt)* })?), * $(,)* ) => {
+        let (timestamp, http_date) = $crate::rocket_include_static_resources_macros::build_time!();
+
+        $resources.set_last_modified_precomputed(::std::time::UNIX_EPOCH + ::std::time::Duration::from_secs(timestamp), http_date);
+
+        $resources.register_table($crate::rocket_include_static_resources_macros::static_table!($crate, $($name => $path $(; { $($metadata)* })?),*));
+    };
+}
@@ -1,46 +1,117 @@
-use super::StaticResources;
-use crate::{EtagIfNoneMatch, StaticResponse};
+use super::{StaticResources, StaticResponse};
+use crate::{fingerprint, Preconditions, Resource, ResourceError, ResourceKey};
 
 /// To monitor the state of static resources.
 #[derive(Debug)]
 pub struct StaticContextManager {
-    pub resources: StaticResources,
+    pub resources:    StaticResources,
+    fingerprint_base: Option<String>,
 }
 
 impl StaticContextManager {
     #[inline]
-    pub(crate) fn new(resources: StaticResources) -> StaticContextManager {
+    pub(crate) fn new(
+        resources: StaticResources,
+        fingerprint_base: Option<String>,
+    ) -> StaticContextManager {
         StaticContextManager {
             resources,
+            fingerprint_base,
         }
     }
 
-    /// Build a `StaticResponse`.
+    /// Get the resources.
     #[inline]
-    pub fn build<S: AsRef<str>>(
+    pub fn resources(&self) -> &StaticResources {
+        &self.resources
+    }
+
+    /// Check whether a resource is registered with the name.
+    #[inline]
+    pub fn contains<K: ResourceKey>(&self, key: K) -> bool {
+        self.resources.get_resource_entry(&key).is_some()
+    }
+
+    /// Get the URL which contains the hash of the content of a resource. `None` if fingerprinted URLs are not enabled in the fairing, or the resource does not exist.
+    #[inline]
+    pub fn fingerprinted_url<K: ResourceKey>(&self, key: K) -> Option<String> {
+        let base = self.fingerprint_base.as_ref()?;
+
+        self.fingerprinted_name(key).map(|name| fingerprint::join_url(base, &name))
+    }
+
+    /// Get the names of the resources along with their fingerprinted URLs, e.g. to write a manifest. Nothing if fingerprinted URLs are not enabled in the fairing.
+    #[inline]
+    pub fn fingerprinted_urls(&self) -> impl Iterator<Item = (&'static str, String)> + '_ {
+        let names =
+            if self.fingerprint_base.is_some() { self.resources.names() } else { Vec::new() };
+
+        names.into_iter().filter_map(|name| self.fingerprinted_url(name).map(|url| (name, url)))
+    }
+
+    #[inline]
+    pub(crate) fn fingerprinted_name<K: ResourceKey>(&self, key: K) -> Option<String> {
+        self.resources
+            .get_resource_entry(&key)
+            .map(|resource| fingerprint::fingerprinted_name(key.name(), &resource.etag))
+    }
+
+    /// Get the Subresource Integrity metadata of a resource, e.g. `sha384-...`, which can be the value of an `integrity` attribute. `None` if the resource does not exist.
+    #[inline]
+    pub fn integrity<K: ResourceKey>(&self, key: K) -> Option<String> {
+        self.resources
+            .get_resource_entry(&key)
+            .map(|resource| resource.integrity.as_ref().to_string())
+    }
+
+    /// Get a view of a resource.
+    #[inline]
+    pub fn get_resource<K: ResourceKey>(&self, key: K) -> Result<Resource, ResourceError> {
+        self.resources
+            .get_resource_entry(&key)
+            .map(|resource| {
+                let (last_modified, _) = self.resources.last_modified_of(&resource);
+
+                Resource::from_data(
+                    resource.mime.clone(),
+                    resource.data.clone(),
+                    resource.etag.clone(),
+                    Some(last_modified),
+                )
+            })
+            .ok_or_else(|| ResourceError::not_found(key.name()))
+    }
+
+    /// Build a `StaticResponse`. The preconditions can be a `&Preconditions` or an `&EtagIfNoneMatch`, and they are evaluated against the representation to send when responding. If the resource has precompressed representations, the one to send is negotiated with the `Accept-Encoding` header of the request. If there is no resource with the name, the response is `404 Not Found`, and the error is logged.
+    #[inline]
+    pub fn build<P: Into<Preconditions>, K: ResourceKey>(
         &self,
-        etag_if_none_match: &EtagIfNoneMatch<'_>,
-        name: S,
+        preconditions: P,
+        key: K,
     ) -> StaticResponse {
-        self.try_build(etag_if_none_match, name).unwrap()
+        self.try_build(preconditions, key).unwrap_or_else(StaticResponse::from)
     }
 
     /// Attempt to build a `StaticResponse`.
     #[inline]
-    pub fn try_build<S: AsRef<str>>(
+    pub fn try_build<P: Into<Preconditions>, K: ResourceKey>(
         &self,
-        etag_if_none_match: &EtagIfNoneMatch<'_>,
-        name: S,
-    ) -> Result<StaticResponse, std::io::Error> {
+        preconditions: P,
+        key: K,
+    ) -> Result<StaticResponse, ResourceError> {
         self.resources
-            .get_resource(name.as_ref())
+            .get_resource_entry(&key)
             .map(|resource| {
-                if etag_if_none_match.weak_eq(resource.2) {
-                    StaticResponse::not_modified()
-                } else {
-                    StaticResponse::build(&resource.0, resource.1, resource.2)
-                }
+                let (last_modified, last_modified_header) =
+                    self.resources.last_modified_of(&resource);
+
+                StaticResponse::build(
+                    resource,
+                    last_modified,
+                    last_modified_header,
+                    preconditions.into(),
+                )
             })
-            .ok_or(std::io::Error::new(std::io::ErrorKind::NotFound, format!("{} not found", name.as_ref())))
+            .ok_or_else(|| ResourceError::not_found(key.name()))
     }
 }
@@ -1,12 +1,16 @@
+pub(crate) mod overlay;
 mod static_resources;
 mod static_response;
 
 mod manager;
 
+#[cfg(not(hot_reload))]
 mod fairing;
 
+#[cfg(not(hot_reload))]
 mod macros;
 
+#[cfg(not(hot_reload))]
 pub use fairing::*;
 pub use manager::*;
 pub use static_resources::*;
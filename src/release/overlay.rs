@@ -0,0 +1,459 @@
+use std::{
+    collections::HashMap,
+    fs, io,
+    path::{Component, Path, PathBuf},
+    pin::pin,
+    sync::{Arc, PoisonError, RwLock},
+    time::{Duration, SystemTime},
+};
+
+use super::static_resources::{Resource, Rewrite, Rewritten, StaticResources};
+use crate::{
+    rocket::{
+        tokio::{self, time},
+        Build, Orbit, Rocket,
+    },
+    substitution::Substitutions,
+};
+
+/// The key of the Rocket configuration which sets the overlay directory, e.g. `ROCKET_STATIC_RESOURCES_OVERLAY=/etc/my-app/overrides`.
+const DIRECTORY_CONFIG_KEY: &str = "static_resources_overlay";
+
+/// The key of the Rocket configuration which sets how often (in seconds) the overlay directory is scanned, e.g. `ROCKET_STATIC_RESOURCES_OVERLAY_INTERVAL=30`.
+const INTERVAL_CONFIG_KEY: &str = "static_resources_overlay_interval";
+
+#[derive(Debug)]
+struct OverlayFile {
+    resource: Arc<Resource>,
+    len:      u64,
+    mtime:    Option<SystemTime>,
+}
+
+/// The files in a directory which shadow the embedded resources with the same names.
+#[derive(Debug)]
+pub(crate) struct Overlay {
+    directory:   PathBuf,
+    embedded:    HashMap<&'static str, Arc<Resource>>,
+    substituted: HashMap<&'static str, Arc<Substitutions>>,
+    rewrite:     Option<Arc<Rewrite>>,
+    // the files after substituting them
+    files:       RwLock<HashMap<&'static str, OverlayFile>>,
+    // the files whose references are rewritten, and the embedded resources which are rewritten again because they refer to the files
+    resources:   RwLock<HashMap<&'static str, Arc<Resource>>>,
+}
+
+impl Overlay {
+    pub(crate) fn new(
+        directory: PathBuf,
+        embedded: HashMap<&'static str, Arc<Resource>>,
+        substituted: HashMap<&'static str, Arc<Substitutions>>,
+        rewrite: Option<Arc<Rewrite>>,
+    ) -> Result<Overlay, io::Error> {
+        if !directory.is_dir() {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("{:?} is not a directory", directory),
+            ));
+        }
+
+        let overlay = Overlay {
+            directory,
+            embedded,
+            substituted,
+            rewrite,
+            files: RwLock::new(HashMap::new()),
+            resources: RwLock::new(HashMap::new()),
+        };
+
+        overlay.scan()?;
+
+        Ok(overlay)
+    }
+
+    #[inline]
+    pub(crate) fn get(&self, name: &str) -> Option<Arc<Resource>> {
+        self.resources.read().unwrap_or_else(PoisonError::into_inner).get(name).cloned()
+    }
+
+    /// Look for the file of every registered name. The files whose sizes and modification times have not changed are kept without being read again. Every file which can be read is used even if another one fails, and the first error is returned.
+    pub(crate) fn scan(&self) -> Result<(), io::Error> {
+        let mut files = HashMap::with_capacity(self.embedded.len());
+        let mut first_error = None;
+
+        for (&name, embedded) in self.embedded.iter() {
+            let path = match join(&self.directory, name) {
+                Some(path) => path,
+                None => continue,
+            };
+
+            match self.scan_file(name, &path, embedded) {
+                Ok(Some(file)) => {
+                    files.insert(name, file);
+                },
+                Ok(None) => (),
+                Err(err) => {
+                    // keep serving the previous file
+                    if let Some(file) = self.take_unchanged(name, None) {
+                        files.insert(name, file);
+                    }
+
+                    first_error.get_or_insert(err);
+                },
+            }
+        }
+
+        let resources = self.rewrite(&files);
+
+        *self.files.write().unwrap_or_else(PoisonError::into_inner) = files;
+        *self.resources.write().unwrap_or_else(PoisonError::into_inner) = resources;
+
+        match first_error {
+            Some(err) => Err(err),
+            None => Ok(()),
+        }
+    }
+
+    /// Read the file of a resource, and substitute it if the resource is substituted.
+    fn scan_file(
+        &self,
+        name: &'static str,
+        path: &Path,
+        embedded: &Resource,
+    ) -> Result<Option<OverlayFile>, io::Error> {
+        let file_metadata = match path.metadata() {
+            Ok(file_metadata) if file_metadata.is_file() => file_metadata,
+            Ok(_) => return Ok(None),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err),
+        };
+
+        let len = file_metadata.len();
+        let mtime = file_metadata.modified().ok();
+
+        if let Some(file) = self.take_unchanged(name, Some((len, mtime))) {
+            return Ok(Some(file));
+        }
+
+        let mut data = fs::read(path)?;
+
+        if let Some(substitutions) = self.substituted.get(name) {
+            data = substitutions.apply(&data).map_err(|message| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("Cannot substitute {:?}. {}", path, message),
+                )
+            })?;
+        }
+
+        let mime = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .and_then(|extension| mime_guess::from_ext(extension).first())
+            .unwrap_or_else(|| embedded.mime.clone());
+
+        Ok(Some(OverlayFile {
+            resource: Arc::new(Resource::owned(mime, Arc::new(data), mtime, embedded.metadata)),
+            len,
+            mtime,
+        }))
+    }
+
+    /// Rewrite the references in the files, and in the embedded resources which refer to the files, if the references are rewritten.
+    fn rewrite(
+        &self,
+        files: &HashMap<&'static str, OverlayFile>,
+    ) -> HashMap<&'static str, Arc<Resource>> {
+        let mut resources = files
+            .iter()
+            .map(|(&name, file)| (name, file.resource.clone()))
+            .collect::<HashMap<_, _>>();
+
+        let rewrite = match self.rewrite.as_deref() {
+            Some(rewrite) if !files.is_empty() => rewrite,
+            _ => return resources,
+        };
+
+        // a file, or an embedded resource before it is rewritten
+        let source = |name: &str| {
+            let (&name, embedded) = self.embedded.get_key_value(name)?;
+
+            let resource = match files.get(name) {
+                Some(file) => &file.resource,
+                None => rewrite.sources.get(name).unwrap_or(embedded),
+            };
+
+            Some((name, resource.clone()))
+        };
+
+        let mut rewritten = HashMap::new();
+
+        for &name in files.keys().chain(rewrite.sources.keys()) {
+            rewrite.rewritten_resource(&source, name, 0, &mut rewritten);
+        }
+
+        for (name, rewritten) in rewritten {
+            let resource = match rewritten {
+                Rewritten::Changed(resource) => resource,
+                _ => continue,
+            };
+
+            // an embedded resource which does not refer to any file is served as it is
+            if !files.contains_key(name)
+                && self
+                    .embedded
+                    .get(name)
+                    .is_some_and(|embedded| embedded.etag.strong_eq(&resource.etag))
+            {
+                continue;
+            }
+
+            resources.insert(name, resource);
+        }
+
+        resources
+    }
+
+    /// Get the current file of a resource, if its size and modification time are the given ones, or unconditionally if `None`.
+    fn take_unchanged(
+        &self,
+        name: &str,
+        state: Option<(u64, Option<SystemTime>)>,
+    ) -> Option<OverlayFile> {
+        let files = self.files.read().unwrap_or_else(PoisonError::into_inner);
+
+        let file = files.get(name)?;
+
+        match state {
+            Some((len, mtime))
+                if file.len != len || file.mtime.is_none() || file.mtime != mtime =>
+            {
+                None
+            },
+            _ => Some(OverlayFile {
+                resource: file.resource.clone(),
+                len:      file.len,
+                mtime:    file.mtime,
+            }),
+        }
+    }
+}
+
+/// Join a resource name to the overlay directory. Names which are not plain relative paths, e.g. with `..`, never match a file.
+fn join(directory: &Path, name: &str) -> Option<PathBuf> {
+    let mut path = directory.to_path_buf();
+
+    for component in Path::new(name).components() {
+        match component {
+            Component::Normal(component) => path.push(component),
+            _ => return None,
+        }
+    }
+
+    Some(path)
+}
+
+/// Use the overlay directory in the Rocket configuration, if any.
+pub(crate) fn configure(
+    rocket: &Rocket<Build>,
+    resources: &mut StaticResources,
+) -> Result<(), String> {
+    let directory = match rocket.figment().extract_inner::<PathBuf>(DIRECTORY_CONFIG_KEY) {
+        Ok(directory) => directory,
+        Err(_) => return Ok(()),
+    };
+
+    resources.set_overlay_directory(directory.clone()).map_err(|err| {
+        format!("Cannot use {:?} as the overlay directory of static resources: {}", directory, err)
+    })
+}
+
+/// Scan the overlay directory again on `SIGHUP` (on Unix), and periodically if the interval is configured, until Rocket shuts down.
+pub(crate) fn watch(rocket: &Rocket<Orbit>, overlay: Arc<Overlay>) {
+    let interval = rocket
+        .figment()
+        .extract_inner::<u64>(INTERVAL_CONFIG_KEY)
+        .ok()
+        .filter(|&seconds| seconds > 0)
+        .map(Duration::from_secs);
+
+    if cfg!(not(unix)) && interval.is_none() {
+        return;
+    }
+
+    let shutdown = rocket.shutdown();
+
+    tokio::spawn(async move {
+        let mut shutdown = pin!(shutdown);
+
+        #[cfg(unix)]
+        let mut hangup = match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::hangup())
+        {
+            Ok(hangup) => Some(hangup),
+            Err(err) => {
+                rocket::error!(
+                    "Cannot listen to SIGHUP for the overlay of static resources: {}",
+                    err
+                );
+
+                None
+            },
+        };
+
+        let mut ticker =
+            interval.map(|interval| time::interval_at(time::Instant::now() + interval, interval));
+
+        loop {
+            tokio::select! {
+                _ = &mut shutdown => break,
+                _ = wait_hangup(
+                    #[cfg(unix)]
+                    &mut hangup,
+                ) => (),
+                _ = tick(&mut ticker) => (),
+            }
+
+            let overlay = overlay.clone();
+
+            match tokio::task::spawn_blocking(move || overlay.scan()).await {
+                Ok(Ok(())) => (),
+                Ok(Err(err)) => {
+                    rocket::error!("Cannot scan the overlay directory of static resources: {}", err)
+                },
+                Err(err) => {
+                    rocket::error!("Cannot scan the overlay directory of static resources: {}", err)
+                },
+            }
+        }
+    });
+}
+
+#[cfg(unix)]
+async fn wait_hangup(hangup: &mut Option<tokio::signal::unix::Signal>) {
+    if let Some(signal) = hangup.as_mut() {
+        if signal.recv().await.is_some() {
+            return;
+        }
+
+        *hangup = None;
+    }
+
+    std::future::pending().await
+}
+
+#[cfg(not(unix))]
+async fn wait_hangup() {
+    std::future::pending().await
+}
+
+async fn tick(ticker: &mut Option<time::Interval>) {
+    match ticker.as_mut() {
+        Some(ticker) => {
+            ticker.tick().await;
+        },
+        None => std::future::pending().await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::process;
+
+    use super::*;
+    use crate::{fingerprint, mime};
+
+    fn resources(directory: &Path) -> StaticResources {
+        let mut resources = StaticResources::new();
+
+        resources.register_resource_static(
+            "index.html",
+            mime::TEXT_HTML,
+            b"<title>{{TITLE}}</title><link href=\"app.css\">",
+        );
+        resources.register_resource_static("app.css", mime::TEXT_CSS, b"body { color: black }");
+
+        let rocket = crate::rocket::custom(
+            crate::rocket::Config::figment()
+                .merge(("static_resources_substitutions", HashMap::from([("TITLE", "Home")]))),
+        );
+
+        resources.substitute("index.html", &Arc::new(Substitutions::from_rocket(&rocket))).unwrap();
+        resources.rewrite_references("/static", "/assets");
+        resources.set_overlay_directory(directory).unwrap();
+
+        resources
+    }
+
+    fn data(resources: &StaticResources, name: &str) -> String {
+        let resource = resources.get_resource_entry(name).unwrap();
+
+        String::from_utf8(resource.data.as_ref().to_vec()).unwrap()
+    }
+
+    fn css_url(resources: &StaticResources) -> String {
+        let css = resources.get_resource_entry("app.css").unwrap();
+
+        fingerprint::join_url("/assets", &fingerprint::fingerprinted_name("app.css", &css.etag))
+    }
+
+    #[test]
+    fn substitute_and_rewrite_files() {
+        let directory = std::env::temp_dir().join(format!("overlay-test-{}", process::id()));
+
+        fs::create_dir_all(&directory).unwrap();
+
+        let resources = resources(&directory);
+
+        let embedded_css_url = css_url(&resources);
+
+        assert_eq!(
+            data(&resources, "index.html"),
+            format!("<title>Home</title><link href=\"{}\">", embedded_css_url)
+        );
+
+        // the embedded page refers to the file of the stylesheet
+        fs::write(directory.join("app.css"), "body { color: red }").unwrap();
+        resources.reload_overlay().unwrap();
+
+        let css_url = css_url(&resources);
+
+        assert_ne!(css_url, embedded_css_url);
+        assert_eq!(
+            data(&resources, "index.html"),
+            format!("<title>Home</title><link href=\"{}\">", css_url)
+        );
+
+        // the file of the page is substituted and rewritten
+        fs::write(
+            directory.join("index.html"),
+            "<h1>{{TITLE}}</h1><link href=\"/static/app.css\">",
+        )
+        .unwrap();
+        resources.reload_overlay().unwrap();
+
+        assert_eq!(
+            data(&resources, "index.html"),
+            format!("<h1>Home</h1><link href=\"{}\">", css_url)
+        );
+
+        // a placeholder without a value keeps the previous file
+        fs::write(directory.join("index.html"), "<h1>{{MISSING}}</h1>").unwrap();
+
+        assert!(resources.reload_overlay().is_err());
+        assert_eq!(
+            data(&resources, "index.html"),
+            format!("<h1>Home</h1><link href=\"{}\">", css_url)
+        );
+
+        // the embedded resources are served again without the files
+        fs::remove_dir_all(&directory).unwrap();
+        fs::create_dir_all(&directory).unwrap();
+        resources.reload_overlay().unwrap();
+
+        assert_eq!(
+            data(&resources, "index.html"),
+            format!("<title>Home</title><link href=\"{}\">", embedded_css_url)
+        );
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
+}
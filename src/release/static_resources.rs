@@ -1,26 +1,345 @@
-use std::collections::HashMap;
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    io,
+    path::PathBuf,
+    sync::{Arc, OnceLock},
+    time::SystemTime,
+};
 
-use crate::{functions::compute_data_etag, mime::Mime, EntityTag};
+use super::overlay::Overlay;
+use crate::{
+    fingerprint,
+    functions::{compute_content_digest, compute_data_etag, compute_integrity, precomputed_etag},
+    mime::{self, Mime},
+    resource::Data,
+    rewrite,
+    substitution::Substitutions,
+    table::ResourceTable,
+    ContentEncoding, EntityTag, ResourceKey, ResourceMetadata,
+};
 
 #[derive(Debug)]
-struct Resource {
-    mime: Mime,
-    data: &'static [u8],
-    etag: EntityTag<'static>,
+pub(crate) struct EncodedResource {
+    pub(crate) encoding:       ContentEncoding,
+    pub(crate) data:           &'static [u8],
+    pub(crate) etag:           EntityTag<'static>,
+    pub(crate) etag_header:    HeaderValue,
+    pub(crate) content_digest: HeaderValue,
+}
+
+/// An embedded resource, or a file in the overlay directory, along with its header values, which are rendered once, when it is registered.
+#[derive(Debug)]
+pub(crate) struct Resource {
+    pub(crate) mime:           Mime,
+    pub(crate) content_type:   HeaderValue,
+    pub(crate) data:           Data,
+    pub(crate) etag:           EntityTag<'static>,
+    pub(crate) etag_header:    HeaderValue,
+    pub(crate) integrity:      HeaderValue,
+    pub(crate) content_digest: HeaderValue,
+    pub(crate) encoded:        Vec<EncodedResource>,
+    pub(crate) metadata:       ResourceMetadata,
+    // the last modification date and its HTTP-date, if it is not the one of all of the embedded resources
+    pub(crate) last_modified:  Option<(SystemTime, HeaderValue)>,
+}
+
+impl Resource {
+    fn new(
+        mime: Mime,
+        data: &'static [u8],
+        encoded: &[(ContentEncoding, &'static [u8])],
+    ) -> Resource {
+        let etag = compute_data_etag(data);
+
+        let encoded = encoded
+            .iter()
+            .map(|&(encoding, data)| {
+                let etag = compute_data_etag(data);
+
+                EncodedResource {
+                    encoding,
+                    data,
+                    etag_header: HeaderValue::from(etag.to_string()),
+                    etag,
+                    content_digest: HeaderValue::from(compute_content_digest(data)),
+                }
+            })
+            .collect();
+
+        Resource {
+            content_type: HeaderValue::from(mime.to_string()),
+            mime,
+            data: Data::Static(data),
+            etag_header: HeaderValue::from(etag.to_string()),
+            etag,
+            integrity: HeaderValue::from(compute_integrity(data)),
+            content_digest: HeaderValue::from(compute_content_digest(data)),
+            encoded,
+            metadata: ResourceMetadata::NONE,
+            last_modified: None,
+        }
+    }
+
+    /// Create a resource from data which is not embedded, e.g. a file in the overlay directory. The MIME type in the metadata overrides the given one.
+    pub(crate) fn owned(
+        mime: Mime,
+        data: Arc<dyn AsRef<[u8]> + Send + Sync>,
+        modified: Option<SystemTime>,
+        metadata: ResourceMetadata,
+    ) -> Resource {
+        let mime = metadata.mime().and_then(|mime| mime.parse().ok()).unwrap_or(mime);
+
+        let bytes = data.as_ref().as_ref();
+
+        let etag = compute_data_etag(bytes);
+        let integrity = compute_integrity(bytes);
+        let content_digest = compute_content_digest(bytes);
+
+        Resource {
+            content_type: HeaderValue::from(mime.to_string()),
+            mime,
+            data: Data::Shared(data),
+            etag_header: HeaderValue::from(etag.to_string()),
+            etag,
+            integrity: HeaderValue::from(integrity),
+            content_digest: HeaderValue::from(content_digest),
+            encoded: Vec::new(),
+            metadata,
+            last_modified: modified
+                .map(|modified| (modified, HeaderValue::from(httpdate::fmt_http_date(modified)))),
+        }
+    }
+
+    /// Create a resource which replaces the data of this one, e.g. after substituting or rewriting it, with the same MIME type, metadata and last modification date. The precompressed representations are dropped, because they would be out of date.
+    fn with_data(&self, data: Vec<u8>) -> Resource {
+        Resource {
+            last_modified: self.last_modified.clone(),
+            ..Resource::owned(self.mime.clone(), Arc::new(data), None, self.metadata)
+        }
+    }
+
+    fn precomputed(
+        mime: &'static str,
+        data: &'static [u8],
+        etag: &'static str,
+        (integrity, content_digest): (&'static str, &'static str),
+        encoded: &[(ContentEncoding, &'static [u8], &'static str, &'static str)],
+        metadata: ResourceMetadata,
+    ) -> Resource {
+        let mime = metadata.mime().unwrap_or(mime);
+
+        let (mime, content_type) = match mime.parse() {
+            Ok(parsed) => (parsed, mime),
+            Err(_) => (mime::APPLICATION_OCTET_STREAM, "application/octet-stream"),
+        };
+
+        let encoded = encoded
+            .iter()
+            .map(|&(encoding, data, etag, content_digest)| {
+                let (etag, etag_header) = precomputed_etag(data, etag);
+
+                EncodedResource {
+                    encoding,
+                    data,
+                    etag,
+                    etag_header: etag_header.into(),
+                    content_digest: content_digest.into(),
+                }
+            })
+            .collect();
+
+        let (etag, etag_header) = precomputed_etag(data, etag);
+
+        Resource {
+            mime,
+            content_type: content_type.into(),
+            data: Data::Static(data),
+            etag,
+            etag_header: etag_header.into(),
+            integrity: integrity.into(),
+            content_digest: content_digest.into(),
+            encoded,
+            metadata,
+            last_modified: None,
+        }
+    }
+}
+
+/// The value of a header, which is borrowed if it is rendered at compile time, or shared if it is rendered at runtime, so that it is freed along with its resource.
+#[derive(Debug, Clone)]
+pub(crate) enum HeaderValue {
+    Static(&'static str),
+    Shared(Arc<str>),
+}
+
+impl HeaderValue {
+    /// Get the value for a response. A value rendered at runtime is copied, because Rocket only takes borrowed or owned strings.
+    #[inline]
+    pub(crate) fn to_header(&self) -> Cow<'static, str> {
+        match self {
+            HeaderValue::Static(value) => Cow::Borrowed(value),
+            HeaderValue::Shared(value) => Cow::Owned(String::from(&**value)),
+        }
+    }
+}
+
+impl AsRef<str> for HeaderValue {
+    #[inline]
+    fn as_ref(&self) -> &str {
+        match self {
+            HeaderValue::Static(value) => value,
+            HeaderValue::Shared(value) => value,
+        }
+    }
+}
+
+impl From<&'static str> for HeaderValue {
+    #[inline]
+    fn from(value: &'static str) -> Self {
+        HeaderValue::Static(value)
+    }
+}
+
+impl From<String> for HeaderValue {
+    #[inline]
+    fn from(value: String) -> Self {
+        HeaderValue::Shared(Arc::from(value))
+    }
+}
+
+impl From<Cow<'static, str>> for HeaderValue {
+    #[inline]
+    fn from(value: Cow<'static, str>) -> Self {
+        match value {
+            Cow::Borrowed(value) => HeaderValue::Static(value),
+            Cow::Owned(value) => HeaderValue::from(value),
+        }
+    }
+}
+
+/// A resource in a table generated at compile time by `static_resources_initializer!(table; ...)`. Its MIME type and entity tags are parsed when it is looked up for the first time.
+#[derive(Debug)]
+pub struct StaticEntry {
+    name:     &'static str,
+    mime:     &'static str,
+    data:     &'static [u8],
+    etag:     &'static str,
+    digests:  (&'static str, &'static str),
+    encoded:  &'static [(ContentEncoding, &'static [u8], &'static str, &'static str)],
+    metadata: ResourceMetadata,
+    resource: OnceLock<Arc<Resource>>,
+}
+
+impl StaticEntry {
+    /// Create an entry. The entity tags and the digests are expected to be computed beforehand, like `StaticResources::register_resource_static_precomputed`.
+    #[inline]
+    pub const fn new(
+        name: &'static str,
+        mime: &'static str,
+        data: &'static [u8],
+        etag: &'static str,
+        digests: (&'static str, &'static str),
+        encoded: &'static [(ContentEncoding, &'static [u8], &'static str, &'static str)],
+        metadata: ResourceMetadata,
+    ) -> StaticEntry {
+        StaticEntry {
+            name,
+            mime,
+            data,
+            etag,
+            digests,
+            encoded,
+            metadata,
+            resource: OnceLock::new(),
+        }
+    }
+
+    /// Get the name of the resource.
+    #[inline]
+    pub const fn name(&self) -> &'static str {
+        self.name
+    }
+
+    #[inline]
+    fn resource(&self) -> &Arc<Resource> {
+        self.resource.get_or_init(|| {
+            Arc::new(Resource::precomputed(
+                self.mime,
+                self.data,
+                self.etag,
+                self.digests,
+                self.encoded,
+                self.metadata,
+            ))
+        })
+    }
 }
 
 #[derive(Debug)]
 /// Static resources.
+///
+/// They are registered one by one, or as a table generated at compile time, which is sorted by name so that no map has to be built when Rocket ignites.
+///
+/// The files in an overlay directory shadow the embedded resources with the same names.
 pub struct StaticResources {
-    resources: HashMap<&'static str, Resource>,
+    resources:            ResourceTable<Arc<Resource>>,
+    table:                &'static [StaticEntry],
+    last_modified:        SystemTime,
+    last_modified_header: HeaderValue,
+    // the substitutions of the substituted resources and how the references are rewritten, which the files in the overlay directory go through too
+    substituted:          HashMap<&'static str, Arc<Substitutions>>,
+    rewrite:              Option<Arc<Rewrite>>,
+    overlay:              Option<Arc<Overlay>>,
 }
 
 impl StaticResources {
-    /// Create an instance of `StaticResources`.
+    /// Create an instance of `StaticResources`. The last modification date of the resources is the current time until `set_last_modified` is called.
     #[inline]
     pub fn new() -> StaticResources {
+        let last_modified = SystemTime::now();
+
         StaticResources {
-            resources: HashMap::new()
+            resources: ResourceTable::new(),
+            table: &[],
+            last_modified,
+            last_modified_header: HeaderValue::from(httpdate::fmt_http_date(last_modified)),
+            substituted: HashMap::new(),
+            rewrite: None,
+            overlay: None,
+        }
+    }
+
+    /// Set the last modification date of the resources, which is usually the time they were built.
+    #[inline]
+    pub fn set_last_modified(&mut self, last_modified: SystemTime) {
+        self.last_modified = last_modified;
+        self.last_modified_header = HeaderValue::from(httpdate::fmt_http_date(last_modified));
+    }
+
+    /// Set the last modification date of the resources along with its HTTP-date, which the macros render at compile time.
+    #[inline]
+    pub fn set_last_modified_precomputed(
+        &mut self,
+        last_modified: SystemTime,
+        http_date: &'static str,
+    ) {
+        self.last_modified = last_modified;
+        self.last_modified_header = http_date.into();
+    }
+
+    /// Get the last modification date of the resources.
+    #[inline]
+    pub fn last_modified(&self) -> SystemTime {
+        self.last_modified
+    }
+
+    /// Get the last modification date of a resource and its HTTP-date.
+    #[inline]
+    pub(crate) fn last_modified_of(&self, resource: &Resource) -> (SystemTime, HeaderValue) {
+        match &resource.last_modified {
+            Some((last_modified, header)) => (*last_modified, header.clone()),
+            None => (self.last_modified, self.last_modified_header.clone()),
         }
     }
 
@@ -32,32 +351,486 @@ impl StaticResources {
         mime: Mime,
         data: &'static [u8],
     ) {
-        let etag = compute_data_etag(data);
+        self.register_resource_static_encoded(name, mime, data, &[]);
+    }
 
-        let resource = Resource {
-            mime,
-            data,
-            etag,
-        };
+    /// Register a static resource along with its precompressed representations, which are ordered by preference.
+    #[inline]
+    pub fn register_resource_static_encoded(
+        &mut self,
+        name: &'static str,
+        mime: Mime,
+        data: &'static [u8],
+        encoded: &[(ContentEncoding, &'static [u8])],
+    ) {
+        self.resources.insert(name, Arc::new(Resource::new(mime, data, encoded)));
+    }
+
+    /// Register a resource whose data is owned, e.g. a `Vec<u8>`, a `String`, an `Arc<[u8]>` or a `Bytes`, such as a `config.js` built from environment variables. It is not copied, and its entity tag is computed when it is registered. Its last modification date is the one of all of the resources (see `set_last_modified`).
+    #[inline]
+    pub fn register_resource_owned<D: AsRef<[u8]> + Send + Sync + 'static>(
+        &mut self,
+        name: &'static str,
+        mime: Mime,
+        data: D,
+    ) {
+        self.resources.insert(
+            name,
+            Arc::new(Resource::owned(mime, Arc::new(data), None, ResourceMetadata::NONE)),
+        );
+    }
+
+    /// Register a resource whose data is produced by `f`, which is called once, when the resource is registered (the fairing registers resources when Rocket ignites).
+    #[inline]
+    pub fn register_resource_generated<D: AsRef<[u8]> + Send + Sync + 'static, F: FnOnce() -> D>(
+        &mut self,
+        name: &'static str,
+        mime: Mime,
+        f: F,
+    ) {
+        self.register_resource_owned(name, mime, f());
+    }
+
+    /// Register a static resource along with its precompressed representations, whose entity tags and digests are computed beforehand (the macros compute them at compile time), so the data is not hashed when it is registered. The entity tags should be quoted, e.g. `"\"xyz\""`, so that they can be the values of the `ETag` header as they are. `digests` are the Subresource Integrity metadata (`sha384-...`) and the value of the `Content-Digest` header (`sha-256=:...:`), and each precompressed representation has its own `Content-Digest` value. The MIME type in the metadata overrides `mime`, and the MIME type falls back to `application/octet-stream` if it cannot be parsed.
+    #[allow(clippy::too_many_arguments)]
+    #[inline]
+    pub fn register_resource_static_precomputed(
+        &mut self,
+        name: &'static str,
+        mime: &'static str,
+        data: &'static [u8],
+        etag: &'static str,
+        digests: (&'static str, &'static str),
+        encoded: &[(ContentEncoding, &'static [u8], &'static str, &'static str)],
+        metadata: ResourceMetadata,
+    ) {
+        self.resources.insert(
+            name,
+            Arc::new(Resource::precomputed(mime, data, etag, digests, encoded, metadata)),
+        );
+    }
+
+    /// Register a table of resources generated at compile time, which replaces the previous one. The resources registered one by one take precedence over the ones in the table.
+    ///
+    /// # Panics
+    ///
+    /// The entries must be sorted by name without repeated names, which the macros guarantee, because they are looked up by a binary search.
+    #[inline]
+    pub fn register_table(&mut self, table: &'static [StaticEntry]) {
+        if let Some(entries) = table.windows(2).find(|entries| entries[0].name >= entries[1].name) {
+            panic!(
+                "The table of static resources is not sorted by name: {:?} is followed by {:?}.",
+                entries[0].name, entries[1].name
+            );
+        }
 
-        self.resources.insert(name, resource);
+        self.table = table;
+    }
+
+    /// Use a directory whose files shadow the embedded resources with the same names, e.g. the file `terms.html` in the directory is served instead of the resource named `terms.html`. It should be called after all of the resources are registered, because only their names are looked for. The MIME type of a file is guessed from its extension, or is the one of the embedded resource if it cannot be guessed. The files of substituted resources are substituted, and the references in the files are rewritten like the ones in the embedded resources, along with the references to the files in the embedded resources, if the fairing substitutes or rewrites resources (it uses the directory in the configuration after doing so). The directory is scanned immediately, and again by `reload_overlay`.
+    pub fn set_overlay_directory<P: Into<PathBuf>>(
+        &mut self,
+        directory: P,
+    ) -> Result<(), io::Error> {
+        let embedded = self
+            .resources
+            .iter()
+            .chain(self.table.iter().map(|entry| (entry.name, entry.resource())))
+            .map(|(name, resource)| (name, resource.clone()))
+            .collect();
+
+        let overlay = Overlay::new(
+            directory.into(),
+            embedded,
+            self.substituted.clone(),
+            self.rewrite.clone(),
+        )?;
+
+        self.overlay = Some(Arc::new(overlay));
+
+        Ok(())
+    }
+
+    /// Scan the overlay directory again, so that added, modified and removed files take effect. The files which have not changed are not read again. It does nothing if there is no overlay directory.
+    #[inline]
+    pub fn reload_overlay(&self) -> Result<(), io::Error> {
+        match self.overlay.as_ref() {
+            Some(overlay) => overlay.scan(),
+            None => Ok(()),
+        }
     }
 
-    /// Get the specific resource.
     #[inline]
-    pub fn get_resource<S: AsRef<str>>(
+    pub(crate) fn overlay(&self) -> Option<&Arc<Overlay>> {
+        self.overlay.as_ref()
+    }
+
+    /// Get the specific embedded resource. The overlay directory is not taken into account.
+    #[inline]
+    pub fn get_resource<K: ResourceKey>(
+        &self,
+        key: K,
+    ) -> Option<(&Mime, &[u8], &EntityTag<'static>)> {
+        self.get_embedded_entry(&key)
+            .map(|(_, resource)| (&resource.mime, resource.data.as_ref(), &resource.etag))
+    }
+
+    /// Get the Subresource Integrity metadata (`sha384-...`) of the specific resource, from the overlay directory if the file of it is there.
+    #[inline]
+    pub fn integrity<K: ResourceKey>(&self, key: K) -> Option<Cow<'static, str>> {
+        self.get_resource_entry(&key).map(|resource| resource.integrity.to_header())
+    }
+
+    /// Get the precompressed representation of the specific embedded resource which is encoded with `encoding`.
+    #[inline]
+    pub fn get_resource_encoded<K: ResourceKey>(
         &self,
-        name: S,
+        key: K,
+        encoding: ContentEncoding,
     ) -> Option<(&Mime, &'static [u8], &EntityTag<'static>)> {
-        let name = name.as_ref();
+        self.get_embedded_entry(&key).and_then(|(_, resource)| {
+            resource
+                .encoded
+                .iter()
+                .find(|encoded| encoded.encoding == encoding)
+                .map(|encoded| (&resource.mime, encoded.data, &encoded.etag))
+        })
+    }
+
+    /// Replace the placeholders in a resource. The precompressed representations of the resource are dropped, because they would be out of date.
+    pub(crate) fn substitute(
+        &mut self,
+        name: &str,
+        substitutions: &Arc<Substitutions>,
+    ) -> Result<(), String> {
+        let (name, resource) = self
+            .get_embedded_entry(name)
+            .ok_or_else(|| format!("The resource {:?} to substitute is not registered.", name))?;
+
+        let data = substitutions
+            .apply(resource.data.as_ref())
+            .map_err(|message| format!("Cannot substitute the resource {:?}. {}", name, message))?;
+
+        let resource = resource.with_data(data);
+
+        self.resources.insert(name, Arc::new(resource));
+        self.substituted.insert(name, substitutions.clone());
+
+        Ok(())
+    }
 
-        self.resources.get(name).map(|resource| (&resource.mime, resource.data, &resource.etag))
+    /// Rewrite the references in HTML and CSS resources to other resources, so that they use the fingerprinted URLs under `fingerprint_base`. `base` is the URL where the resources are served by their names. The precompressed representations of a rewritten resource are dropped.
+    pub(crate) fn rewrite_references(&mut self, base: &str, fingerprint_base: &str) {
+        let names = self
+            .resources
+            .iter()
+            .chain(self.table.iter().map(|entry| (entry.name, entry.resource())))
+            .filter(|(_, resource)| rewrite::Kind::of(&resource.mime).is_some())
+            .map(|(name, _)| name)
+            .collect::<Vec<_>>();
+
+        let mut rewrite = Rewrite {
+            base:             base.to_string(),
+            fingerprint_base: fingerprint_base.to_string(),
+            sources:          HashMap::new(),
+        };
+
+        let mut rewritten = HashMap::new();
+
+        let source = |name: &str| {
+            self.get_embedded_entry(name).map(|(name, resource)| (name, resource.clone()))
+        };
+
+        for name in names {
+            rewrite.rewritten_resource(&source, name, 0, &mut rewritten);
+        }
+
+        let rewritten = rewritten
+            .into_iter()
+            .filter_map(|(name, rewritten)| match rewritten {
+                Rewritten::Changed(resource) => Some((name, resource)),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        for (name, resource) in rewritten {
+            if let Some((_, source)) = self.get_embedded_entry(name) {
+                rewrite.sources.insert(name, source.clone());
+            }
+
+            self.resources.insert(name, resource);
+        }
+
+        self.rewrite = Some(Arc::new(rewrite));
+    }
+
+    /// Get the names of the resources, the registered ones in the order of registration followed by the ones in the table.
+    #[inline]
+    pub fn names(&self) -> Vec<&'static str> {
+        self.resources
+            .iter()
+            .map(|(name, _)| name)
+            .chain(
+                // substituted and rewritten entries of the table are registered again
+                self.table
+                    .iter()
+                    .map(|entry| entry.name)
+                    .filter(|name| self.resources.get(name).is_none()),
+            )
+            .collect()
+    }
+
+    /// Get a resource, from the overlay directory if the file of it is there.
+    #[inline]
+    pub(crate) fn get_resource_entry<K: ResourceKey + ?Sized>(
+        &self,
+        key: &K,
+    ) -> Option<Arc<Resource>> {
+        if let Some(overlay) = self.overlay.as_ref() {
+            if let Some(resource) = overlay.get(key.name()) {
+                return Some(resource);
+            }
+        }
+
+        self.get_embedded_entry(key).map(|(_, resource)| resource.clone())
+    }
+
+    #[inline]
+    fn get_embedded_entry<K: ResourceKey + ?Sized>(
+        &self,
+        key: &K,
+    ) -> Option<(&'static str, &Arc<Resource>)> {
+        if let Some(entry) = self.resources.get(key) {
+            return Some(entry);
+        }
+
+        let name = key.name();
+
+        self.table.binary_search_by(|entry| entry.name.cmp(name)).ok().map(|index| {
+            let entry = &self.table[index];
+
+            (entry.name, entry.resource())
+        })
     }
 }
 
+/// How the references in resources are rewritten, along with the rewritten resources before they are rewritten, so that they can be rewritten again when the resources which they refer to are shadowed by the files in the overlay directory.
+#[derive(Debug)]
+pub(crate) struct Rewrite {
+    base:               String,
+    fingerprint_base:   String,
+    pub(crate) sources: HashMap<&'static str, Arc<Resource>>,
+}
+
+impl Rewrite {
+    /// Get a resource whose references are rewritten, and keep it in `rewritten`. `source` gets a resource before it is rewritten, along with its name. `None` if there is no such resource, or the reference to it closes a cycle or is too deep, so that it should be left as it is.
+    pub(crate) fn rewritten_resource<F: Fn(&str) -> Option<(&'static str, Arc<Resource>)>>(
+        &self,
+        source: &F,
+        name: &str,
+        depth: usize,
+        rewritten: &mut HashMap<&'static str, Rewritten>,
+    ) -> Option<Arc<Resource>> {
+        let (name, resource) = source(name)?;
+
+        match rewritten.get(name) {
+            // its fingerprint is not known until it is rewritten
+            Some(Rewritten::InProgress) => return None,
+            Some(Rewritten::Unchanged) => return Some(resource),
+            Some(Rewritten::Changed(new_resource)) => return Some(new_resource.clone()),
+            None => (),
+        }
+
+        let kind = match rewrite::Kind::of(&resource.mime) {
+            Some(kind) => kind,
+            None => return Some(resource),
+        };
+
+        if depth >= rewrite::MAX_DEPTH {
+            return None;
+        }
+
+        rewritten.insert(name, Rewritten::InProgress);
+
+        let data = rewrite::rewrite(name, resource.data.as_ref(), kind, &self.base, |reference| {
+            self.rewritten_resource(source, reference, depth + 1, rewritten).map(|resource| {
+                fingerprint::join_url(
+                    &self.fingerprint_base,
+                    &fingerprint::fingerprinted_name(reference, &resource.etag),
+                )
+            })
+        });
+
+        match data {
+            Some(data) => {
+                let new_resource = Arc::new(resource.with_data(data));
+
+                rewritten.insert(name, Rewritten::Changed(new_resource.clone()));
+
+                Some(new_resource)
+            },
+            None => {
+                rewritten.insert(name, Rewritten::Unchanged);
+
+                Some(resource)
+            },
+        }
+    }
+}
+
+/// The state of a resource while the references are rewritten.
+#[derive(Debug)]
+pub(crate) enum Rewritten {
+    /// Its references are being rewritten, so a reference to it closes a cycle.
+    InProgress,
+    Unchanged,
+    Changed(Arc<Resource>),
+}
+
 impl Default for StaticResources {
     #[inline]
     fn default() -> Self {
         StaticResources::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const fn entry(name: &'static str) -> StaticEntry {
+        StaticEntry::new(name, "text/plain", b"", "", ("", ""), &[], ResourceMetadata::NONE)
+    }
+
+    static SORTED: [StaticEntry; 3] = [entry("a.txt"), entry("b.txt"), entry("b/c.txt")];
+    static UNSORTED: [StaticEntry; 2] = [entry("b.txt"), entry("a.txt")];
+    static REPEATED: [StaticEntry; 2] = [entry("a.txt"), entry("a.txt")];
+
+    #[test]
+    fn register_sorted_table() {
+        let mut resources = StaticResources::new();
+
+        resources.register_table(&SORTED);
+
+        assert!(resources.get_resource("b/c.txt").is_some());
+        assert!(resources.get_resource("c.txt").is_none());
+    }
+
+    #[test]
+    fn list_names() {
+        let mut resources = StaticResources::new();
+
+        resources.register_table(&SORTED);
+        resources.register_resource_owned("z.txt", mime::TEXT_PLAIN, "z");
+        // shadows the entry of the table
+        resources.register_resource_owned("b.txt", mime::TEXT_PLAIN, "b");
+
+        assert_eq!(["z.txt", "b.txt", "a.txt", "b/c.txt"].as_slice(), resources.names());
+    }
+
+    #[test]
+    fn keep_last_modified() {
+        let built = SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1_600_000_000);
+
+        let mut resources = StaticResources::new();
+
+        resources.set_last_modified_precomputed(built, "Sun, 13 Sep 2020 12:26:40 GMT");
+        resources.register_resource_static("env.js", mime::APPLICATION_JAVASCRIPT, b"{{API}}");
+        resources.register_resource_owned("config.js", mime::APPLICATION_JAVASCRIPT, "config");
+
+        let rocket = crate::rocket::custom(
+            crate::rocket::Config::figment()
+                .merge(("static_resources_substitutions", HashMap::from([("API", "/api")]))),
+        );
+
+        resources.substitute("env.js", &Arc::new(Substitutions::from_rocket(&rocket))).unwrap();
+
+        for name in ["env.js", "config.js"] {
+            let resource = resources.get_resource_entry(name).unwrap();
+
+            let (last_modified, header) = resources.last_modified_of(&resource);
+
+            assert_eq!(
+                (last_modified, header.as_ref()),
+                (built, "Sun, 13 Sep 2020 12:26:40 GMT"),
+                "{}",
+                name
+            );
+        }
+
+        assert_eq!(resources.get_resource("env.js").unwrap().1, b"/api");
+    }
+
+    #[test]
+    fn share_runtime_header_values() {
+        let mut resources = StaticResources::new();
+
+        resources.register_resource_static_precomputed(
+            "a.txt",
+            "text/plain",
+            b"a",
+            "\"a\"",
+            ("sha384-a", "sha-256=:a:"),
+            &[],
+            ResourceMetadata::NONE,
+        );
+        resources.register_resource_owned("b.txt", mime::TEXT_PLAIN, "b");
+
+        let a = resources.get_resource_entry("a.txt").unwrap();
+
+        for value in [&a.content_type, &a.etag_header, &a.integrity, &a.content_digest] {
+            assert!(matches!(value, HeaderValue::Static(_)), "{:?}", value);
+        }
+
+        let b = resources.get_resource_entry("b.txt").unwrap();
+
+        let values =
+            [&b.content_type, &b.etag_header, &b.integrity, &b.content_digest].map(|value| {
+                match value {
+                    HeaderValue::Shared(value) => Arc::downgrade(value),
+                    HeaderValue::Static(value) => panic!("{:?}", value),
+                }
+            });
+
+        // the values are freed along with the replaced resource
+        drop(b);
+        resources.register_resource_owned("b.txt", mime::TEXT_PLAIN, "c");
+
+        assert!(values.iter().all(|value| value.upgrade().is_none()));
+    }
+
+    #[test]
+    fn leave_cycles_unrewritten() {
+        let mut resources = StaticResources::new();
+
+        resources.register_resource_static("a.css", mime::TEXT_CSS, b"@import url(\"a.css\");");
+        resources.register_resource_static("b.css", mime::TEXT_CSS, b"@import url(c.css);");
+        resources.register_resource_static("c.css", mime::TEXT_CSS, b"@import url(b.css);");
+
+        resources.rewrite_references("/static", "/assets");
+
+        assert_eq!(resources.get_resource("a.css").unwrap().1, b"@import url(\"a.css\");");
+
+        // the stylesheet which is rewritten first refers to the other one, which is left as it is
+        let b = resources.get_resource("b.css").unwrap().1;
+        let c = resources.get_resource("c.css").unwrap().1;
+
+        match (b.starts_with(b"@import url(/assets/c."), c.starts_with(b"@import url(/assets/b.")) {
+            (true, false) => assert_eq!(c, b"@import url(b.css);"),
+            (false, true) => assert_eq!(b, b"@import url(c.css);"),
+            _ => panic!("{:?} {:?}", String::from_utf8_lossy(b), String::from_utf8_lossy(c)),
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "not sorted")]
+    fn reject_unsorted_table() {
+        StaticResources::new().register_table(&UNSORTED);
+    }
+
+    #[test]
+    #[should_panic(expected = "not sorted")]
+    fn reject_repeated_names() {
+        StaticResources::new().register_table(&REPEATED);
+    }
+}
@@ -1,65 +1,180 @@
-use std::io::Cursor;
-
-use mime::Mime;
+use std::{io::Cursor, ops::Range, sync::Arc, time::SystemTime};
 
+use super::static_resources::{HeaderValue, Resource};
 use crate::{
+    range::{self, RangeResponse},
+    resource::Data,
     rocket::{
         http::Status,
         request::Request,
         response::{self, Responder, Response},
     },
-    EntityTag,
+    ContentEncoding, Preconditions, ResourceError,
 };
 
 #[derive(Debug)]
-struct StaticResponseInner {
-    mime: String,
-    data: &'static [u8],
-    etag: String,
+struct Content {
+    resource:             Arc<Resource>,
+    last_modified:        SystemTime,
+    last_modified_header: HeaderValue,
+    preconditions:        Preconditions,
 }
 
+/// The body of a response, which shares the data of a resource.
 #[derive(Debug)]
-/// To respond a static resource.
+struct Body {
+    data:  Data,
+    range: Range<usize>,
+}
+
+impl AsRef<[u8]> for Body {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        &self.data.as_ref()[self.range.clone()]
+    }
+}
+
+#[derive(Debug)]
+/// To respond a static resource, or an error if the resource cannot be built, which is `404 Not Found` for an unknown name.
+///
+/// The header values are rendered when the resource is registered, so responding a resource included by the macros does not allocate anything besides what Rocket needs, and the values of a resource created at runtime are only copied.
 pub struct StaticResponse {
-    inner: Option<StaticResponseInner>,
+    content: Result<Content, ResourceError>,
 }
 
 impl StaticResponse {
     #[inline]
     pub(crate) fn build(
-        mime: &Mime,
-        data: &'static [u8],
-        etag: &EntityTag<'static>,
+        resource: Arc<Resource>,
+        last_modified: SystemTime,
+        last_modified_header: HeaderValue,
+        preconditions: Preconditions,
     ) -> StaticResponse {
         StaticResponse {
-            inner: Some(StaticResponseInner {
-                mime: mime.to_string(),
-                data,
-                etag: etag.to_string(),
+            content: Ok(Content {
+                resource,
+                last_modified,
+                last_modified_header,
+                preconditions,
             }),
         }
     }
+}
 
+impl From<ResourceError> for StaticResponse {
     #[inline]
-    pub(crate) const fn not_modified() -> StaticResponse {
+    fn from(error: ResourceError) -> Self {
         StaticResponse {
-            inner: None
+            content: Err(error)
         }
     }
 }
 
 impl<'r, 'o: 'r> Responder<'r, 'o> for StaticResponse {
     #[inline]
-    fn respond_to(self, _: &'r Request<'_>) -> response::Result<'o> {
+    fn respond_to(self, request: &'r Request<'_>) -> response::Result<'o> {
+        match self.content {
+            Ok(content) => content.respond_to(request),
+            Err(error) => error.respond_to(request),
+        }
+    }
+}
+
+impl<'r, 'o: 'r> Responder<'r, 'o> for Content {
+    #[inline]
+    fn respond_to(self, request: &'r Request<'_>) -> response::Result<'o> {
+        let resource = &self.resource;
+
+        let encoding = ContentEncoding::negotiate(
+            request.headers().get_one("Accept-Encoding"),
+            resource.encoded.iter().map(|encoded| encoded.encoding),
+        );
+
+        let (data, etag, etag_header, content_digest) = match encoding.and_then(|encoding| {
+            resource.encoded.iter().find(|encoded| encoded.encoding == encoding)
+        }) {
+            Some(encoded) => (
+                Data::Static(encoded.data),
+                &encoded.etag,
+                &encoded.etag_header,
+                &encoded.content_digest,
+            ),
+            None => (
+                resource.data.clone(),
+                &resource.etag,
+                &resource.etag_header,
+                &resource.content_digest,
+            ),
+        };
+
+        let len = data.as_ref().len();
+
         let mut response = Response::build();
 
-        if let Some(inner) = self.inner {
-            response.raw_header("Etag", inner.etag.to_string());
-            response.raw_header("Content-Type", inner.mime.to_string());
+        if !resource.encoded.is_empty() {
+            response.raw_header("Vary", "Accept-Encoding");
+        }
+
+        response.raw_header("Etag", etag_header.to_header());
+
+        resource.metadata.apply_headers(&mut response);
+
+        match self.preconditions.evaluate(request.method(), etag, Some(self.last_modified)) {
+            Some(status) => {
+                response.status(status);
+            },
+            None => {
+                response.raw_header("Last-Modified", self.last_modified_header.to_header());
+                response.raw_header("Accept-Ranges", "bytes");
+
+                if let Some(encoding) = encoding {
+                    response.raw_header("Content-Encoding", encoding.as_str());
+                }
+
+                match RangeResponse::evaluate(
+                    request,
+                    data.as_ref(),
+                    resource.content_type.as_ref(),
+                    etag,
+                    Some(self.last_modified),
+                ) {
+                    RangeResponse::Full => {
+                        response.raw_header("Content-Type", resource.content_type.to_header());
+                        response.raw_header("Content-Digest", content_digest.to_header());
+
+                        response.sized_body(
+                            len,
+                            Cursor::new(Body {
+                                data,
+                                range: 0..len,
+                            }),
+                        );
+                    },
+                    RangeResponse::Single(range) => {
+                        response.status(Status::PartialContent);
+                        response.raw_header("Content-Type", resource.content_type.to_header());
+                        response.raw_header("Content-Range", range::content_range(&range, len));
+
+                        response.sized_body(
+                            range.len(),
+                            Cursor::new(Body {
+                                data,
+                                range,
+                            }),
+                        );
+                    },
+                    RangeResponse::Multiple(content_type, body) => {
+                        response.status(Status::PartialContent);
+                        response.raw_header("Content-Type", content_type);
 
-            response.sized_body(inner.data.len(), Cursor::new(inner.data));
-        } else {
-            response.status(Status::NotModified);
+                        response.sized_body(body.len(), Cursor::new(body));
+                    },
+                    RangeResponse::Unsatisfiable => {
+                        response.status(Status::RangeNotSatisfiable);
+                        response.raw_header("Content-Range", range::unsatisfied_content_range(len));
+                    },
+                }
+            },
         }
 
         response.ok()
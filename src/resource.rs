@@ -0,0 +1,222 @@
+use std::{
+    fmt::{self, Debug, Formatter},
+    str,
+    sync::Arc,
+    time::SystemTime,
+};
+
+use crate::{mime::Mime, EntityTag};
+
+/// The content of a resource, which is cheap to clone.
+#[derive(Clone)]
+pub(crate) enum Data {
+    #[cfg(embed)]
+    Static(&'static [u8]),
+    Shared(Arc<dyn AsRef<[u8]> + Send + Sync>),
+}
+
+impl AsRef<[u8]> for Data {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        match self {
+            #[cfg(embed)]
+            Data::Static(data) => data,
+            Data::Shared(data) => data.as_ref().as_ref(),
+        }
+    }
+}
+
+impl Debug for Data {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Debug::fmt(self.as_ref(), f)
+    }
+}
+
+/// A view of a resource, which has the same methods in every mode. It is a snapshot, so it does not change even if the file of the resource is reloaded later.
+#[derive(Debug, Clone)]
+pub struct Resource {
+    mime:     Mime,
+    data:     Data,
+    etag:     EntityTag<'static>,
+    modified: Option<SystemTime>,
+}
+
+impl Resource {
+    #[cfg(embed)]
+    #[inline]
+    pub(crate) fn from_data(
+        mime: Mime,
+        data: Data,
+        etag: EntityTag<'static>,
+        modified: Option<SystemTime>,
+    ) -> Resource {
+        Resource {
+            mime,
+            data,
+            etag,
+            modified,
+        }
+    }
+
+    #[cfg(hot_reload)]
+    #[inline]
+    pub(crate) fn from_shared(
+        mime: Mime,
+        data: Arc<Vec<u8>>,
+        etag: EntityTag<'static>,
+        modified: Option<SystemTime>,
+    ) -> Resource {
+        Resource {
+            mime,
+            data: Data::Shared(data),
+            etag,
+            modified,
+        }
+    }
+
+    /// Get the content.
+    #[inline]
+    pub fn bytes(&self) -> &[u8] {
+        self.data.as_ref()
+    }
+
+    /// Get the content as a string slice. `None` if it is not UTF-8.
+    #[inline]
+    pub fn as_str(&self) -> Option<&str> {
+        str::from_utf8(self.bytes()).ok()
+    }
+
+    /// Get the MIME type.
+    #[inline]
+    pub fn mime(&self) -> &Mime {
+        &self.mime
+    }
+
+    /// Get the entity tag of the content.
+    #[inline]
+    pub fn etag(&self) -> &EntityTag<'static> {
+        &self.etag
+    }
+
+    /// Get the length of the content in bytes.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.bytes().len()
+    }
+
+    /// Check whether the content is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.bytes().is_empty()
+    }
+
+    /// Get the last modification date, which is the build time of embedded resources, or the modification time of the file otherwise (including a file in the overlay directory). `None` if the file system does not provide it.
+    #[inline]
+    pub fn modified(&self) -> Option<SystemTime> {
+        self.modified
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::{
+        functions::compute_data_etag,
+        rocket::{
+            http::{Header, Status},
+            local::blocking::Client,
+        },
+        EmbeddedFileServer, ResourceMode, StaticContextManager, StaticResponse,
+    };
+
+    #[test]
+    fn round_trip_owned_and_generated_resources() {
+        let resources: [(&str, &[u8]); 3] =
+            [("vec.bin", b"vec"), ("arc.bin", b"arc"), ("generated.bin", b"generated")];
+
+        for &mode in ResourceMode::AVAILABLE {
+            let registered = SystemTime::now();
+
+            let rocket = rocket::build()
+                .attach(
+                    StaticResponse::fairing(|resources| {
+                        resources.register_resource_owned(
+                            "vec.bin",
+                            mime::APPLICATION_OCTET_STREAM,
+                            b"vec".to_vec(),
+                        );
+                        resources.register_resource_owned(
+                            "arc.bin",
+                            mime::APPLICATION_OCTET_STREAM,
+                            Arc::<[u8]>::from(&b"arc"[..]),
+                        );
+                        resources.register_resource_generated(
+                            "generated.bin",
+                            mime::APPLICATION_OCTET_STREAM,
+                            || String::from("generated"),
+                        );
+                    })
+                    .mode(mode),
+                )
+                .mount("/", EmbeddedFileServer::new());
+
+            let client = Client::untracked(rocket).unwrap();
+
+            let manager = client.rocket().state::<StaticContextManager>().unwrap();
+
+            for (name, data) in resources {
+                let etag = compute_data_etag(data);
+
+                let resource = manager.get_resource(name).unwrap();
+
+                assert_eq!(data, resource.bytes(), "{:?} {}", mode, name);
+                assert!(resource.etag().strong_eq(&etag), "{:?} {}", mode, name);
+
+                let modified = resource.modified().unwrap();
+
+                assert!(
+                    modified + Duration::from_secs(1) >= registered
+                        && modified <= SystemTime::now(),
+                    "{:?} {}",
+                    mode,
+                    name
+                );
+
+                let response = client.get(format!("/{}", name)).dispatch();
+
+                assert_eq!(Status::Ok, response.status(), "{:?} {}", mode, name);
+
+                let etag_header = response.headers().get_one("ETag").map(String::from);
+                let last_modified = response.headers().get_one("Last-Modified").map(String::from);
+
+                assert_eq!(Some(data), response.into_bytes().as_deref(), "{:?} {}", mode, name);
+                assert_eq!(Some(etag.to_string()), etag_header, "{:?} {}", mode, name);
+                assert_eq!(
+                    Some(httpdate::fmt_http_date(modified)),
+                    last_modified,
+                    "{:?} {}",
+                    mode,
+                    name
+                );
+
+                // the validators are accepted in the conditional requests
+                let response = client
+                    .get(format!("/{}", name))
+                    .header(Header::new("If-None-Match", etag.to_string()))
+                    .dispatch();
+
+                assert_eq!(Status::NotModified, response.status(), "{:?} {}", mode, name);
+
+                let response = client
+                    .get(format!("/{}", name))
+                    .header(Header::new("If-Modified-Since", last_modified.unwrap()))
+                    .dispatch();
+
+                assert_eq!(Status::NotModified, response.status(), "{:?} {}", mode, name);
+            }
+        }
+    }
+}
@@ -0,0 +1,369 @@
+use std::{ops::Range, str};
+
+use crate::mime::{self, Mime};
+
+/// How deep the references between resources are followed. A reference to an HTML or CSS resource which is deeper than this is left as it is, like a reference which closes a cycle, e.g. a stylesheet which imports itself, directly or not.
+pub(crate) const MAX_DEPTH: usize = 8;
+
+/// The kinds of resources whose references are rewritten.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Kind {
+    /// The `src` and `href` attributes.
+    Html,
+    /// The `url()` functions.
+    Css,
+}
+
+impl Kind {
+    #[inline]
+    pub(crate) fn of(mime: &Mime) -> Option<Kind> {
+        match (mime.type_(), mime.subtype()) {
+            (mime::TEXT, mime::HTML) => Some(Kind::Html),
+            (mime::TEXT, mime::CSS) => Some(Kind::Css),
+            _ => None,
+        }
+    }
+}
+
+/// Rewrite the references in the data of the resource `name` to registered resources. `url_of` gets the name of a resource and returns its new URL, or `None` if it is not a registered resource. `None` is returned if nothing is rewritten.
+pub(crate) fn rewrite<F: FnMut(&str) -> Option<String>>(
+    name: &str,
+    data: &[u8],
+    kind: Kind,
+    base: &str,
+    mut url_of: F,
+) -> Option<Vec<u8>> {
+    let references = match kind {
+        Kind::Html => html_references(data),
+        Kind::Css => css_references(data),
+    };
+
+    let mut output = Vec::new();
+    let mut copied = 0;
+    let mut rewritten = false;
+
+    for range in references {
+        let reference = match str::from_utf8(&data[range.clone()]) {
+            Ok(reference) => reference,
+            Err(_) => continue,
+        };
+
+        let (referenced, suffix) = match resource_name(name, reference, base) {
+            Some(referenced) => referenced,
+            None => continue,
+        };
+
+        let url = match url_of(&referenced) {
+            Some(url) => url,
+            None => continue,
+        };
+
+        output.extend_from_slice(&data[copied..range.start]);
+        output.extend_from_slice(url.as_bytes());
+        output.extend_from_slice(suffix.as_bytes());
+
+        copied = range.end;
+        rewritten = true;
+    }
+
+    if !rewritten {
+        return None;
+    }
+
+    output.extend_from_slice(&data[copied..]);
+
+    Some(output)
+}
+
+/// Get the name of the resource which a reference points to, along with the query and the fragment. A reference points to a resource if it is the URL of the resource under `base`, or a relative URL which is resolved against the URL of the referring resource `name` under `base`.
+fn resource_name<'a>(name: &str, reference: &'a str, base: &str) -> Option<(String, &'a str)> {
+    let (path, suffix) = reference.split_at(reference.find(['?', '#']).unwrap_or(reference.len()));
+
+    if let Some(name) =
+        path.strip_prefix(base.trim_end_matches('/')).and_then(|path| path.strip_prefix('/'))
+    {
+        return Some((name.to_string(), suffix)).filter(|(name, _)| !name.is_empty());
+    }
+
+    // absolute URLs and URLs with a scheme, e.g. `data:`, point to something else
+    if path.is_empty() || path.starts_with('/') || path.contains(':') {
+        return None;
+    }
+
+    let mut segments = name.split('/').collect::<Vec<_>>();
+
+    // the file name of the referring resource
+    segments.pop();
+
+    for segment in path.split('/') {
+        match segment {
+            "." => (),
+            ".." => {
+                segments.pop()?;
+            },
+            _ => segments.push(segment),
+        }
+    }
+
+    Some((segments.join("/"), suffix)).filter(|(name, _)| !name.is_empty() && !name.ends_with('/'))
+}
+
+#[inline]
+fn starts_with_ignore_case(data: &[u8], prefix: &[u8]) -> bool {
+    data.len() >= prefix.len() && data[..prefix.len()].eq_ignore_ascii_case(prefix)
+}
+
+#[inline]
+fn skip_whitespaces(data: &[u8], mut i: usize) -> usize {
+    while i < data.len() && data[i].is_ascii_whitespace() {
+        i += 1;
+    }
+
+    i
+}
+
+#[inline]
+fn find(data: &[u8], i: usize, needle: &[u8]) -> Option<usize> {
+    data.get(i..)?
+        .windows(needle.len())
+        .position(|window| window.eq_ignore_ascii_case(needle))
+        .map(|position| i + position)
+}
+
+/// Find the values of the `src` and `href` attributes of the tags. Text, comments, and the contents of `<script>` and `<style>` elements are skipped.
+fn html_references(data: &[u8]) -> Vec<Range<usize>> {
+    let mut references = Vec::new();
+    let mut i = 0;
+
+    while let Some(start) = find(data, i, b"<") {
+        if starts_with_ignore_case(&data[start..], b"<!--") {
+            i = find(data, start + 4, b"-->").map(|end| end + 3).unwrap_or(data.len());
+
+            continue;
+        }
+
+        // closing tags, doctypes and a `<` in text have no attributes to rewrite
+        if !data.get(start + 1).is_some_and(u8::is_ascii_alphabetic) {
+            i = start + 1;
+
+            continue;
+        }
+
+        let name_end = data[start + 1..]
+            .iter()
+            .position(|&b| b.is_ascii_whitespace() || b == b'>' || b == b'/')
+            .map(|len| start + 1 + len)
+            .unwrap_or(data.len());
+
+        let tag_name = &data[start + 1..name_end];
+
+        i = match tag_attributes(data, name_end, &mut references) {
+            Some(end) => end,
+            // an unclosed tag or quote
+            None => return references,
+        };
+
+        for raw_text in [&b"script"[..], b"style"] {
+            if tag_name.eq_ignore_ascii_case(raw_text) {
+                i = find(data, i, &[b"</", raw_text].concat()).unwrap_or(data.len());
+            }
+        }
+    }
+
+    references
+}
+
+/// Parse the attributes of a tag until its `>`, whose next index is returned, and collect the values of the `src` and `href` attributes.
+fn tag_attributes(data: &[u8], mut i: usize, references: &mut Vec<Range<usize>>) -> Option<usize> {
+    loop {
+        i = skip_whitespaces(data, i);
+
+        match *data.get(i)? {
+            b'>' => return Some(i + 1),
+            b'/' | b'=' => {
+                i += 1;
+
+                continue;
+            },
+            _ => (),
+        }
+
+        let name_start = i;
+
+        while i < data.len()
+            && !data[i].is_ascii_whitespace()
+            && !matches!(data[i], b'=' | b'>' | b'/')
+        {
+            i += 1;
+        }
+
+        let name = &data[name_start..i];
+
+        if let Some((value, end)) = attribute_value(data, i)? {
+            if name.eq_ignore_ascii_case(b"src") || name.eq_ignore_ascii_case(b"href") {
+                references.push(value);
+            }
+
+            i = end;
+        }
+    }
+}
+
+/// Parse `= "value"`, `= 'value'` or `= value` after the name of an attribute, and get the range of the value along with the index after it. It is `Some(None)` if the attribute has no value, and `None` if a quote is not closed.
+fn attribute_value(data: &[u8], i: usize) -> Option<Option<(Range<usize>, usize)>> {
+    let i = skip_whitespaces(data, i);
+
+    if data.get(i) != Some(&b'=') {
+        return Some(None);
+    }
+
+    let start = skip_whitespaces(data, i + 1);
+
+    match data.get(start) {
+        Some(&quote) if quote == b'"' || quote == b'\'' => {
+            let len = data[start + 1..].iter().position(|&b| b == quote)?;
+
+            Some(Some((start + 1..start + 1 + len, start + 2 + len)))
+        },
+        _ => {
+            let len = data[start..]
+                .iter()
+                .position(|&b| b.is_ascii_whitespace() || b == b'>')
+                .unwrap_or(data.len() - start);
+
+            if len == 0 {
+                Some(None)
+            } else {
+                Some(Some((start..start + len, start + len)))
+            }
+        },
+    }
+}
+
+/// Find the arguments of the `url()` functions.
+fn css_references(data: &[u8]) -> Vec<Range<usize>> {
+    let mut references = Vec::new();
+    let mut i = 0;
+
+    while i < data.len() {
+        let is_function = starts_with_ignore_case(&data[i..], b"url(")
+            && (i == 0
+                || !(data[i - 1].is_ascii_alphanumeric() || matches!(data[i - 1], b'-' | b'_')));
+
+        if !is_function {
+            i += 1;
+
+            continue;
+        }
+
+        let start = skip_whitespaces(data, i + 4);
+
+        let value = match data.get(start) {
+            Some(&quote) if quote == b'"' || quote == b'\'' => data[start + 1..]
+                .iter()
+                .position(|&b| b == quote)
+                .map(|len| start + 1..start + 1 + len),
+            Some(_) => data[start..]
+                .iter()
+                .position(|&b| b.is_ascii_whitespace() || b == b')')
+                .filter(|&len| len > 0)
+                .map(|len| start..start + len),
+            None => None,
+        };
+
+        match value {
+            Some(value) => {
+                i = value.end;
+
+                references.push(value);
+            },
+            None => i = start,
+        }
+    }
+
+    references
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NAMES: [&str; 4] = ["index.html", "app.css", "images/logo.png", "fonts/a.woff2"];
+
+    fn rewrite_as(name: &str, data: &str, kind: Kind) -> Option<String> {
+        rewrite(name, data.as_bytes(), kind, "/static", |reference| {
+            NAMES.contains(&reference).then(|| format!("/assets/{}", reference))
+        })
+        .map(|data| String::from_utf8(data).unwrap())
+    }
+
+    fn rewrite_html(data: &str) -> Option<String> {
+        rewrite_as("index.html", data, Kind::Html)
+    }
+
+    #[test]
+    fn rewrite_quoted_and_unquoted_values() {
+        assert_eq!(
+            rewrite_html(
+                r#"<link href="app.css"><img src='images/logo.png'><img alt=x SRC=images/logo.png>"#
+            )
+            .unwrap(),
+            r#"<link href="/assets/app.css"><img src='/assets/images/logo.png'><img alt=x SRC=/assets/images/logo.png>"#
+        );
+        assert_eq!(
+            rewrite_html(r#"<img src = "/static/images/logo.png" / >"#).unwrap(),
+            r#"<img src = "/assets/images/logo.png" / >"#
+        );
+    }
+
+    #[test]
+    fn keep_query_and_fragment() {
+        assert_eq!(
+            rewrite_html(r#"<link href="app.css?v=1#top"><a href="index.html#main">"#).unwrap(),
+            r#"<link href="/assets/app.css?v=1#top"><a href="/assets/index.html#main">"#
+        );
+    }
+
+    #[test]
+    fn resolve_relative_references() {
+        assert_eq!(
+            rewrite_as("fonts/a.css", "a { background: url(../images/logo.png) }", Kind::Css)
+                .unwrap(),
+            "a { background: url(/assets/images/logo.png) }"
+        );
+        assert_eq!(
+            rewrite_as("fonts/b.css", "@font-face { src: url('./a.woff2') }", Kind::Css).unwrap(),
+            "@font-face { src: url('/assets/fonts/a.woff2') }"
+        );
+        assert_eq!(
+            rewrite_as("fonts/b.css", "a { background: url(../../app.css) }", Kind::Css),
+            None
+        );
+    }
+
+    #[test]
+    fn ignore_other_urls() {
+        assert_eq!(
+            rewrite_html(
+                r#"<img src="data:image/png;base64,AAAA"><a href="https://example.com/app.css"><link href="/other/app.css"><img data-src="app.css">"#
+            ),
+            None
+        );
+        assert_eq!(
+            rewrite_as("app.css", "a { background: url(data:image/png;base64,AAAA) }", Kind::Css),
+            None
+        );
+    }
+
+    #[test]
+    fn only_rewrite_attributes_of_tags() {
+        let html = r#"<p>Use src="app.css" or href=app.css</p><!-- <link href="app.css"> --><script src="app.css">var s = ' src="app.css"';</script><style> a { } </STYLE ><img src="app.css">"#;
+
+        assert_eq!(
+            rewrite_html(html).unwrap(),
+            r#"<p>Use src="app.css" or href=app.css</p><!-- <link href="app.css"> --><script src="/assets/app.css">var s = ' src="app.css"';</script><style> a { } </STYLE ><img src="/assets/app.css">"#
+        );
+
+        assert_eq!(rewrite_html(r#"<img alt="unclosed src="app.css">"#), None);
+    }
+}
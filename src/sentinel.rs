@@ -0,0 +1,133 @@
+use std::marker::PhantomData;
+
+use crate::{
+    rocket::{
+        request::Request,
+        response::{self, Responder},
+        Ignite, Rocket, Sentinel,
+    },
+    StaticContextManager, StaticResponse,
+};
+
+/// A resource known at compile time. The handler macros implement it for the names and the typed handles in them.
+pub trait ResourceName {
+    /// Get the name of the resource.
+    fn name() -> &'static str;
+}
+
+/// Abort the launch if the fairing of static resources is not attached.
+fn abort_without_manager(rocket: &Rocket<Ignite>) -> Option<&StaticContextManager> {
+    let manager = rocket.state::<StaticContextManager>();
+
+    if manager.is_none() {
+        rocket::error!(
+            "`StaticContextManager` is not managed. Attach the fairing of static resources (e.g. \
+             `static_resources_initializer!`) to respond `StaticResponse`."
+        );
+    }
+
+    manager
+}
+
+/// Aborts the launch if the fairing of static resources is not attached.
+impl Sentinel for StaticResponse {
+    #[inline]
+    fn abort(rocket: &Rocket<Ignite>) -> bool {
+        abort_without_manager(rocket).is_none()
+    }
+}
+
+/// A `StaticResponse` of a resource whose name is known at compile time. Besides the fairing of static resources, its `Sentinel` implementation also aborts the launch if the resource is not registered.
+#[derive(Debug)]
+pub struct NamedStaticResponse<N: ResourceName> {
+    response: StaticResponse,
+    _name:    PhantomData<N>,
+}
+
+impl<N: ResourceName> From<StaticResponse> for NamedStaticResponse<N> {
+    #[inline]
+    fn from(response: StaticResponse) -> Self {
+        NamedStaticResponse {
+            response,
+            _name: PhantomData,
+        }
+    }
+}
+
+impl<'r, 'o: 'r, N: ResourceName> Responder<'r, 'o> for NamedStaticResponse<N> {
+    #[inline]
+    fn respond_to(self, request: &'r Request<'_>) -> response::Result<'o> {
+        self.response.respond_to(request)
+    }
+}
+
+impl<N: ResourceName> Sentinel for NamedStaticResponse<N> {
+    fn abort(rocket: &Rocket<Ignite>) -> bool {
+        let manager = match abort_without_manager(rocket) {
+            Some(manager) => manager,
+            None => return true,
+        };
+
+        if !manager.contains(N::name()) {
+            rocket::error!(
+                "The static resource `{}` is used by a route, but it is not registered.",
+                N::name()
+            );
+
+            return true;
+        }
+
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        rocket::{error::ErrorKind, http::Status, local::blocking::Client, routes, Build, Rocket},
+        ResourceMode, StaticResponse,
+    };
+
+    crate::static_response_handler! {
+        "/a.txt" => a => "a.txt",
+    }
+
+    fn rocket(mode: ResourceMode, name: &'static str) -> Rocket<Build> {
+        rocket::build()
+            .attach(
+                StaticResponse::fairing(move |resources| {
+                    resources.register_resource_owned(name, mime::TEXT_PLAIN, "a");
+                })
+                .mode(mode),
+            )
+            .mount("/", routes![a])
+    }
+
+    fn aborted(rocket: Rocket<Build>) -> bool {
+        match Client::untracked(rocket) {
+            Ok(_) => false,
+            Err(err) => matches!(err.kind(), ErrorKind::SentinelAborts(_)),
+        }
+    }
+
+    #[test]
+    fn launch_with_registered_names() {
+        for &mode in ResourceMode::AVAILABLE {
+            let client = Client::untracked(rocket(mode, "a.txt")).unwrap();
+
+            assert_eq!(Status::Ok, client.get("/a.txt").dispatch().status(), "{:?}", mode);
+        }
+    }
+
+    #[test]
+    fn abort_with_unregistered_names() {
+        for &mode in ResourceMode::AVAILABLE {
+            assert!(aborted(rocket(mode, "b.txt")), "{:?}", mode);
+        }
+    }
+
+    #[test]
+    fn abort_without_fairing() {
+        assert!(aborted(rocket::build().mount("/", routes![a])));
+    }
+}
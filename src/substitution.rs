@@ -0,0 +1,97 @@
+use std::{collections::HashMap, env};
+
+use crate::rocket::{Build, Rocket};
+
+/// The key of the Rocket configuration which holds the values of placeholders, e.g. `ROCKET_STATIC_RESOURCES_SUBSTITUTIONS={API_BASE_URL="https://api.example.com"}`.
+const CONFIG_KEY: &str = "static_resources_substitutions";
+
+/// The values which replace the `{{NAME}}` placeholders in resources. A value is looked up in the `static_resources_substitutions` configuration first, and then in the environment variables.
+#[derive(Debug)]
+pub(crate) struct Substitutions {
+    values: HashMap<String, String>,
+}
+
+impl Substitutions {
+    #[inline]
+    pub(crate) fn from_rocket(rocket: &Rocket<Build>) -> Substitutions {
+        let values = rocket
+            .figment()
+            .extract_inner::<HashMap<String, String>>(CONFIG_KEY)
+            .unwrap_or_default();
+
+        Substitutions {
+            values,
+        }
+    }
+
+    #[inline]
+    fn get(&self, name: &str) -> Option<String> {
+        self.values.get(name).cloned().or_else(|| env::var(name).ok())
+    }
+
+    /// Replace every `{{NAME}}` (spaces around `NAME` are allowed) in the data, where `NAME` consists of ASCII letters, digits and underscores. Anything else between braces is kept as it is. A placeholder without a value is an error.
+    pub(crate) fn apply(&self, data: &[u8]) -> Result<Vec<u8>, String> {
+        let mut output = Vec::with_capacity(data.len());
+        let mut rest = data;
+
+        while let Some(start) = find(rest, b"{{") {
+            output.extend_from_slice(&rest[..start]);
+
+            let after_open = &rest[start + 2..];
+
+            match placeholder(after_open) {
+                Some((name, len)) => {
+                    let value = self.get(name).ok_or_else(|| {
+                        format!(
+                            "The placeholder `{{{{{}}}}}` has no value in the `{}` configuration \
+                             or the environment variables.",
+                            name, CONFIG_KEY
+                        )
+                    })?;
+
+                    output.extend_from_slice(value.as_bytes());
+
+                    rest = &after_open[len..];
+                },
+                None => {
+                    output.extend_from_slice(b"{{");
+
+                    rest = after_open;
+                },
+            }
+        }
+
+        output.extend_from_slice(rest);
+
+        Ok(output)
+    }
+}
+
+#[inline]
+fn find(data: &[u8], needle: &[u8]) -> Option<usize> {
+    data.windows(needle.len()).position(|window| window == needle)
+}
+
+/// Parse `NAME }}` after `{{`, and return the name and the length including `}}`.
+fn placeholder(data: &[u8]) -> Option<(&str, usize)> {
+    let start = data.iter().position(|&b| b != b' ')?;
+
+    let len = data[start..].iter().position(|&b| !(b.is_ascii_alphanumeric() || b == b'_'))?;
+
+    if len == 0 {
+        return None;
+    }
+
+    let end = start + len;
+
+    let close = end + data[end..].iter().position(|&b| b != b' ')?;
+
+    if !data[close..].starts_with(b"}}") {
+        return None;
+    }
+
+    // the name is ASCII
+    let name = std::str::from_utf8(&data[start..end]).ok()?;
+
+    Some((name, close + 2))
+}
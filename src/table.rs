@@ -0,0 +1,69 @@
+use std::collections::HashMap;
+
+use crate::ResourceKey;
+
+/// Resources in the order of registration. They are looked up by their names, or directly by their indices if the keys know them.
+#[derive(Debug)]
+pub(crate) struct ResourceTable<T> {
+    entries: Vec<Option<(&'static str, T)>>,
+    indices: HashMap<&'static str, usize>,
+}
+
+impl<T> ResourceTable<T> {
+    #[inline]
+    pub(crate) fn new() -> ResourceTable<T> {
+        ResourceTable {
+            entries: Vec::new(), indices: HashMap::new()
+        }
+    }
+
+    /// Insert a resource. A resource with the same name is replaced in its place and returned.
+    pub(crate) fn insert(&mut self, name: &'static str, value: T) -> Option<T> {
+        match self.indices.get(name) {
+            Some(&index) => self.entries[index].replace((name, value)).map(|(_, value)| value),
+            None => {
+                self.indices.insert(name, self.entries.len());
+                self.entries.push(Some((name, value)));
+
+                None
+            },
+        }
+    }
+
+    /// Remove a resource. Its index is not reused.
+    #[cfg(hot_reload)]
+    #[inline]
+    pub(crate) fn remove(&mut self, name: &str) -> Option<T> {
+        let index = self.indices.remove(name)?;
+
+        self.entries[index].take().map(|(_, value)| value)
+    }
+
+    #[cfg(hot_reload)]
+    #[inline]
+    pub(crate) fn contains<K: ResourceKey + ?Sized>(&self, key: &K) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Get a resource and its registered name.
+    pub(crate) fn get<K: ResourceKey + ?Sized>(&self, key: &K) -> Option<(&'static str, &T)> {
+        let name = key.name();
+
+        if let Some(Some((entry_name, value))) =
+            key.index().and_then(|index| self.entries.get(index))
+        {
+            if *entry_name == name {
+                return Some((entry_name, value));
+            }
+        }
+
+        let index = *self.indices.get(name)?;
+
+        self.entries[index].as_ref().map(|(name, value)| (*name, value))
+    }
+
+    #[inline]
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (&'static str, &T)> {
+        self.entries.iter().flatten().map(|(name, value)| (*name, value))
+    }
+}